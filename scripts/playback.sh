@@ -1,7 +1,7 @@
 #!/usr/bin/env bash
 # scripts/playback.sh — fixture playback regression runner (BRIEF.md quality gate).
 #
-# Runs whisper-cli over test/fixtures/*.wav, diffs against .expected.txt,
+# Runs murmur-cli over test/fixtures/*.wav, diffs against .expected.txt,
 # applies the sanity filter spec'd in test/fixtures/README.md.
 #
 # Usage:
@@ -11,17 +11,17 @@
 #
 # Exit codes: 0 clean (or no fixtures), 1 regression, 2 doctor failure.
 #
-# whisper-cli params here intentionally don't match the app's anti-hallucination
-# settings (set_temperature_inc(0.0), entropy_thold(2.4), no_speech_thold). Drift
-# between CLI and app runtime is a known follow-up; sanity filter still catches
-# the class of stupid output BRIEF.md cares about.
+# murmur-cli runs the same TranscriptionEngine as the app (anti-hallucination
+# params, hallucination filter, initial prompt). --no-settings keeps results
+# independent of the local settings.json. Build it with:
+#   cargo build --release -p murmur-cli --manifest-path src-tauri/Cargo.toml
 
 set -euo pipefail
 
 REPO_ROOT="$(cd "$(dirname "$0")/.." && pwd)"
 FIXTURES_DIR="${FIXTURES_DIR:-${REPO_ROOT}/test/fixtures}"
 WER_TOL="${WER_TOL:-0.10}"
-WHISPER_CLI="${WHISPER_CLI:-whisper-cli}"
+MURMUR_CLI="${MURMUR_CLI:-${REPO_ROOT}/src-tauri/target/release/murmur-cli}"
 WHISPER_MODEL="${WHISPER_MODEL:-${HOME}/.cache/whisper.cpp/ggml-base.bin}"
 WHISPER_LANG="${WHISPER_LANG:-auto}"
 
@@ -32,7 +32,7 @@ if (( ${#fixtures[@]} == 0 )); then
   exit 0
 fi
 
-for cmd in "${WHISPER_CLI}" python3; do
+for cmd in "${MURMUR_CLI}" python3; do
   command -v "${cmd}" >/dev/null 2>&1 || { echo "ERROR: ${cmd} not on PATH" >&2; exit 2; }
 done
 [[ -f "${WHISPER_MODEL}" ]] || { echo "ERROR: model not at ${WHISPER_MODEL} (set WHISPER_MODEL=<path>)" >&2; exit 2; }
@@ -48,9 +48,9 @@ for wav in "${fixtures[@]}"; do
     continue
   fi
 
-  raw="$("${WHISPER_CLI}" -m "${WHISPER_MODEL}" -f "${wav}" -l "${WHISPER_LANG}" -nt 2>/dev/null || true)"
+  raw="$("${MURMUR_CLI}" transcribe --no-settings --model "${WHISPER_MODEL}" --lang "${WHISPER_LANG}" "${wav}" 2>/dev/null || true)"
   if [[ -z "${raw// }" ]]; then
-    printf "FAIL %-30s  whisper-cli empty output\n" "${name}"
+    printf "FAIL %-30s  murmur-cli empty output\n" "${name}"
     fail=$((fail + 1))
     continue
   fi
@@ -62,13 +62,7 @@ raw = os.environ["RAW"]
 ref = open(os.environ["REF_FILE"], encoding="utf-8").read().strip()
 tol = float(os.environ["WER_TOL"])
 
-# safety net: strip timestamps if -nt was ignored by older whisper-cli
-hyp = " ".join(
-    s for s in (
-        re.sub(r"^\[\d\d:\d\d:\d\d\.\d+\s*-->\s*\d\d:\d\d:\d\d\.\d+\]\s*", "", line).strip()
-        for line in raw.splitlines()
-    ) if s
-).strip()
+hyp = " ".join(line.strip() for line in raw.splitlines() if line.strip())
 
 disallowed = [
     ("emoji",     re.compile(r"[\U0001F300-\U0001FAFF✀-➿]")),
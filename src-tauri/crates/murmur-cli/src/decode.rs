@@ -0,0 +1,104 @@
+use std::path::Path;
+use thiserror::Error;
+
+use murmur_core::audio;
+
+#[derive(Debug, Error)]
+pub(crate) enum DecodeError {
+    #[error("unsupported audio format: {0} (expected .wav or .flac)")]
+    Unsupported(String),
+    #[error("failed to read WAV: {0}")]
+    Wav(#[from] hound::Error),
+    #[error("failed to read FLAC: {0}")]
+    Flac(#[from] claxon::Error),
+}
+
+/// Decodes a WAV or FLAC file into 16kHz mono samples ready for Whisper.
+pub(crate) fn load_audio(path: &Path) -> Result<Vec<f32>, DecodeError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("wav") | Some("wave") => load_wav(path),
+        Some("flac") => load_flac(path),
+        other => Err(DecodeError::Unsupported(other.unwrap_or("no extension").to_string())),
+    }
+}
+
+fn load_wav(path: &Path) -> Result<Vec<f32>, DecodeError> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = int_scale(spec.bits_per_sample as u32);
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+    Ok(audio::prepare_samples(&interleaved, spec.channels, spec.sample_rate))
+}
+
+fn load_flac(path: &Path) -> Result<Vec<f32>, DecodeError> {
+    let mut reader = claxon::FlacReader::open(path)?;
+    let info = reader.streaminfo();
+    let scale = int_scale(info.bits_per_sample);
+    let interleaved: Vec<f32> = reader
+        .samples()
+        .map(|s| s.map(|v| v as f32 / scale))
+        .collect::<Result<_, _>>()?;
+    Ok(audio::prepare_samples(&interleaved, info.channels as u16, info.sample_rate))
+}
+
+/// Full-scale value for signed integer PCM of the given bit depth.
+fn int_scale(bits_per_sample: u32) -> f32 {
+    (1u64 << (bits_per_sample.clamp(2, 32) - 1)) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_wav(path: &Path, spec: hound::WavSpec, frames: usize) {
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for _ in 0..frames * spec.channels as usize {
+            writer.write_sample(i16::MAX / 2).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_load_wav_stereo_48k() {
+        let path = std::env::temp_dir().join("murmur-cli-test-stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        write_wav(&path, spec, 48_000);
+
+        let samples = load_audio(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // 1s of audio → 1s at 16kHz mono
+        assert_eq!(samples.len(), 16_000);
+        // Skip the resampler's filter ramp at the edges
+        assert!(samples[50..15_950].iter().all(|s| (s - 0.5).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_load_audio_rejects_unknown_extension() {
+        let err = load_audio(Path::new("meeting.mp3")).unwrap_err();
+        assert!(matches!(err, DecodeError::Unsupported(ext) if ext == "mp3"));
+    }
+
+    #[test]
+    fn test_int_scale() {
+        assert_eq!(int_scale(16), 32_768.0);
+        assert_eq!(int_scale(24), 8_388_608.0);
+    }
+}
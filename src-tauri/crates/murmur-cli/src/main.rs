@@ -0,0 +1,206 @@
+mod decode;
+mod output;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use murmur_core::audio::TARGET_SAMPLE_RATE;
+use murmur_core::pipeline::{Pipeline, PipelineObserver, PipelineRequest};
+use murmur_core::settings::{self, Settings};
+use murmur_core::{llm, model, whisper};
+
+use output::Format;
+
+/// Bundle identifier from tauri.conf.json — the GUI stores settings.json and
+/// models/ under the platform data directory for this identifier.
+const APP_IDENTIFIER: &str = "com.murmur.voice";
+
+#[derive(Parser)]
+#[command(name = "murmur-cli", version, about = "Transcribe audio files with the Murmur Voice engine")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Transcribe WAV or FLAC files
+    Transcribe(TranscribeArgs),
+}
+
+#[derive(Args)]
+struct TranscribeArgs {
+    /// Audio files to transcribe (.wav or .flac)
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Language code (zh, en, ja, ...) or "auto". Defaults to settings.json
+    #[arg(long)]
+    lang: Option<String>,
+    /// Translate the speech into English with the local model instead of
+    /// transcribing it. No LLM is involved
+    #[arg(long, conflicts_with = "enhance")]
+    translate: bool,
+    /// Run LLM post-processing with the AI Processing provider from settings.json
+    #[arg(long)]
+    enhance: bool,
+    /// LLM tone style used with --enhance (formal, casual, technical)
+    #[arg(long, default_value = "default")]
+    style: String,
+    /// Transcription engine: "local", "groq" or "custom". Defaults to settings.json
+    #[arg(long)]
+    engine: Option<String>,
+    /// Whisper model: a ggml file path or a catalog id such as "small".
+    /// Defaults to the model selected in settings.json
+    #[arg(long)]
+    model: Option<PathBuf>,
+    /// Data directory containing settings.json and models/
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+    /// Ignore settings.json (dictionary, packs, replacements) and use defaults
+    #[arg(long)]
+    no_settings: bool,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+/// Prints non-fatal pipeline warnings to stderr.
+struct CliObserver;
+
+impl PipelineObserver for CliObserver {
+    fn warning(&self, message: &str) {
+        eprintln!("warning: {message}");
+    }
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Transcribe(args) => transcribe(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn transcribe(args: &TranscribeArgs) -> Result<(), String> {
+    let data_dir = args
+        .data_dir
+        .clone()
+        .or_else(default_data_dir)
+        .ok_or("could not determine the data directory, pass --data-dir")?;
+
+    let mut settings = if args.no_settings {
+        Settings::default()
+    } else {
+        settings::load_settings(&data_dir)
+    };
+    if let Some(lang) = &args.lang {
+        settings.language = lang.clone();
+    }
+    if let Some(engine) = &args.engine {
+        settings.engine = engine.clone();
+    }
+    // LLM processing is opt-in per invocation, regardless of the GUI toggle
+    settings.llm_enabled = args.enhance;
+    if args.enhance && llm::create_enhancer(&settings).is_none() {
+        return Err("--enhance requires an AI Processing provider configured in settings.json".to_string());
+    }
+
+    let pipeline = Pipeline::new(&settings);
+    let engine = if pipeline.uses_remote_engine() {
+        None
+    } else {
+        // --model takes a path or a catalog id; the default is the model chosen in the app
+        let model_path = match &args.model {
+            Some(path) if path.is_file() => path.clone(),
+            Some(arg) if model::find_model(&arg.to_string_lossy()).is_some() => {
+                model::model_path(&data_dir, &model::ModelConfig::for_model(&arg.to_string_lossy()).filename)
+            }
+            Some(path) => path.clone(),
+            None => model::model_path(&data_dir, &model::ModelConfig::for_model(&settings.model).filename),
+        };
+        if !model_path.is_file() {
+            return Err(format!(
+                "model not found at {} (download it from the app or pass --model)",
+                model_path.display()
+            ));
+        }
+        let can_translate = model_path
+            .file_name()
+            .and_then(|name| model::find_model_by_filename(&name.to_string_lossy()))
+            .is_none_or(|m| m.translates);
+        if args.translate && !can_translate {
+            eprintln!("warning: {} was not trained to translate, output may stay in the spoken language", model_path.display());
+        }
+        let model_path_str = model_path
+            .to_str()
+            .ok_or("model path contains invalid UTF-8")?;
+        Some(whisper::TranscriptionEngine::new(model_path_str).map_err(|e| e.to_string())?)
+    };
+    let pipeline = match &engine {
+        Some(engine) => pipeline.with_engine(engine),
+        None => pipeline,
+    }
+    .with_translation(args.translate);
+
+    let request = PipelineRequest {
+        style: args.style.clone(),
+        ..PipelineRequest::default()
+    };
+
+    let mut failed = 0;
+    for file in &args.files {
+        let samples = match decode::load_audio(file) {
+            Ok(samples) => samples,
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                failed += 1;
+                continue;
+            }
+        };
+        let duration_secs = samples.len() as f64 / TARGET_SAMPLE_RATE as f64;
+
+        let result = match pipeline.run(&samples, &request, &CliObserver) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                failed += 1;
+                continue;
+            }
+        };
+
+        if args.files.len() > 1 && args.format == Format::Text {
+            println!("==> {} <==", file.display());
+        }
+        print!("{}", output::render(args.format, file, &result, duration_secs));
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} of {} files failed", args.files.len()));
+    }
+    Ok(())
+}
+
+/// Mirrors Tauri's `app_data_dir()` so the CLI reads the GUI's settings and models.
+fn default_data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let base = std::env::var_os("HOME")
+        .map(|h| PathBuf::from(h).join("Library").join("Application Support"));
+    #[cfg(target_os = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("share")));
+
+    base.map(|b| b.join(APP_IDENTIFIER))
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use murmur_core::export::{self, ExportFormat};
+use murmur_core::pipeline::PipelineResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Format {
+    /// Plain text, one transcript per file
+    Text,
+    /// One JSON object per file (JSON Lines)
+    Json,
+    /// SubRip subtitles
+    Srt,
+    /// WebVTT subtitles
+    Vtt,
+    /// Markdown with a timestamp per segment
+    Markdown,
+}
+
+#[derive(serde::Serialize)]
+struct JsonOutput<'a> {
+    file: String,
+    duration_secs: f64,
+    #[serde(flatten)]
+    result: &'a PipelineResult,
+}
+
+/// Renders one file's result in the requested format, including the trailing newline.
+pub(crate) fn render(format: Format, file: &Path, result: &PipelineResult, duration_secs: f64) -> String {
+    match format {
+        Format::Text => format!("{}\n", result.text),
+        Format::Json => {
+            let output = JsonOutput {
+                file: file.display().to_string(),
+                duration_secs,
+                result,
+            };
+            // Serializing plain strings/numbers cannot fail
+            format!("{}\n", serde_json::to_string(&output).unwrap_or_default())
+        }
+        Format::Srt => render_export(ExportFormat::Srt, result, duration_secs),
+        Format::Vtt => render_export(ExportFormat::Vtt, result, duration_secs),
+        Format::Markdown => render_export(ExportFormat::Markdown, result, duration_secs),
+    }
+}
+
+fn render_export(format: ExportFormat, result: &PipelineResult, duration_secs: f64) -> String {
+    let duration_ms = (duration_secs.max(0.0) * 1000.0).round() as u64;
+    export::render(&export::for_text(&result.transcript, &result.text, duration_ms), format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use murmur_core::transcript::{Segment, Transcript};
+
+    fn result(text: &str) -> PipelineResult {
+        PipelineResult {
+            raw_text: text.to_string(),
+            text: text.to_string(),
+            engine: "local".to_string(),
+            local: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_text() {
+        let out = render(Format::Text, Path::new("a.wav"), &result("你好"), 1.0);
+        assert_eq!(out, "你好\n");
+    }
+
+    #[test]
+    fn test_render_srt() {
+        let out = render(Format::Srt, Path::new("a.wav"), &result("Hello"), 2.25);
+        assert_eq!(out, "1\n00:00:00,000 --> 00:00:02,250\nHello\n\n");
+        assert!(render(Format::Srt, Path::new("a.wav"), &result(""), 2.25).is_empty());
+    }
+
+    #[test]
+    fn test_render_srt_segments() {
+        let mut r = result("Hello world.");
+        r.transcript = Transcript::from_text("Hello", 1200);
+        r.transcript.segments.push(Segment {
+            start_ms: 1500,
+            end_ms: 2250,
+            text: " world.".to_string(),
+            ..r.transcript.segments[0].clone()
+        });
+        r.transcript.segments[0].text = " Hello".to_string();
+        let out = render(Format::Srt, Path::new("a.wav"), &r, 3.0);
+        assert_eq!(
+            out,
+            "1\n00:00:00,000 --> 00:00:01,200\nHello\n\n2\n00:00:01,500 --> 00:00:02,250\nworld.\n\n"
+        );
+
+        // LLM rewrote the text: segment timing no longer applies
+        r.text = "Hello, world!".to_string();
+        let out = render(Format::Srt, Path::new("a.wav"), &r, 3.0);
+        assert_eq!(out, "1\n00:00:00,000 --> 00:00:03,000\nHello, world!\n\n");
+    }
+
+    #[test]
+    fn test_render_vtt_and_markdown() {
+        let out = render(Format::Vtt, Path::new("a.wav"), &result("A & B"), 61.5);
+        assert_eq!(out, "WEBVTT\n\n00:00:00.000 --> 00:01:01.500\nA &amp; B\n\n");
+        let out = render(Format::Markdown, Path::new("a.wav"), &result("A & B"), 61.5);
+        assert_eq!(out, "# Transcript\n\n**[00:00:00 - 00:01:01]** A & B\n\n");
+    }
+
+    #[test]
+    fn test_render_json() {
+        let out = render(Format::Json, Path::new("a.wav"), &result("Hello"), 2.0);
+        let value: serde_json::Value = serde_json::from_str(out.trim()).unwrap();
+        assert_eq!(value["file"], "a.wav");
+        assert_eq!(value["text"], "Hello");
+        assert_eq!(value["duration_secs"], 2.0);
+        assert_eq!(value["local"], true);
+    }
+}
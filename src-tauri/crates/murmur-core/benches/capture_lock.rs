@@ -0,0 +1,125 @@
+// How long the capture callback waits for and holds the buffer lock while
+// live preview keeps reading the recording. Compares the chunked
+// `SampleBuffer` with cloning a plain `Vec<f32>` on every read, and with
+// the callback handing blocks over with `try_lock` as `CaptureSink` does.
+//
+// Run with `cargo bench -p murmur-core --bench capture_lock`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use murmur_core::buffer::SampleBuffer;
+
+/// Five minutes of 16kHz audio in 10ms callbacks, the toggle-mode maximum.
+const CALLBACKS: usize = 30_000;
+const CALLBACK_SAMPLES: usize = 160;
+/// Time between callbacks and between reads, compressed from 10ms and live
+/// preview's 2s so the run is short but reads still span the recording.
+const CALLBACK_INTERVAL: Duration = Duration::from_micros(20);
+const READ_INTERVAL: Duration = Duration::from_millis(1);
+
+trait Buffer: Send + 'static {
+    fn push(&mut self, samples: &[f32]);
+    /// Read the whole recording the way live preview does; returns its length.
+    fn read(&self) -> usize;
+}
+
+impl Buffer for Vec<f32> {
+    fn push(&mut self, samples: &[f32]) {
+        self.extend_from_slice(samples);
+    }
+
+    fn read(&self) -> usize {
+        self.clone().len()
+    }
+}
+
+impl Buffer for SampleBuffer {
+    fn push(&mut self, samples: &[f32]) {
+        SampleBuffer::push(self, samples);
+    }
+
+    fn read(&self) -> usize {
+        self.snapshot().len()
+    }
+}
+
+struct Report {
+    /// Per callback: waiting for the lock plus appending.
+    callback: Vec<Duration>,
+    reads: usize,
+}
+
+/// With `handoff` the callback never waits: blocks it can't hand over are
+/// kept and pushed with the next one.
+fn run<B: Buffer>(buffer: B, handoff: bool) -> Report {
+    let buffer = Arc::new(Mutex::new(buffer));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let reader = {
+        let buffer = Arc::clone(&buffer);
+        let done = Arc::clone(&done);
+        std::thread::spawn(move || {
+            let mut reads = 0;
+            while !done.load(Ordering::Relaxed) {
+                let len = buffer.lock().unwrap().read();
+                std::hint::black_box(len);
+                reads += 1;
+                std::thread::sleep(READ_INTERVAL);
+            }
+            reads
+        })
+    };
+
+    let block = [0.1f32; CALLBACK_SAMPLES];
+    let mut pending = Vec::with_capacity(4096);
+    let mut callback = Vec::with_capacity(CALLBACKS);
+    for _ in 0..CALLBACKS {
+        let start = Instant::now();
+        if handoff {
+            match buffer.try_lock() {
+                Ok(mut b) => {
+                    if !pending.is_empty() {
+                        b.push(&pending);
+                        pending.clear();
+                    }
+                    b.push(&block);
+                }
+                Err(_) => pending.extend_from_slice(&block),
+            }
+        } else {
+            buffer.lock().unwrap().push(&block);
+        }
+        callback.push(start.elapsed());
+        std::thread::sleep(CALLBACK_INTERVAL);
+    }
+    done.store(true, Ordering::Relaxed);
+    let reads = reader.join().unwrap();
+
+    callback.sort();
+    Report { callback, reads }
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn print(name: &str, report: &Report) {
+    let c = &report.callback;
+    println!(
+        "{name:<14} p50 {:>10.2?}  p99 {:>10.2?}  p99.9 {:>10.2?}  max {:>10.2?}  ({} reads)",
+        percentile(c, 0.5),
+        percentile(c, 0.99),
+        percentile(c, 0.999),
+        c[c.len() - 1],
+        report.reads
+    );
+}
+
+fn main() {
+    println!("capture callback lock time over {CALLBACKS} callbacks ({}s of audio)", CALLBACKS * CALLBACK_SAMPLES / 16_000);
+    print("Vec clone", &run(Vec::<f32>::new(), false));
+    print("SampleBuffer", &run(SampleBuffer::new(), false));
+    print("+ try_lock", &run(SampleBuffer::new(), true));
+}
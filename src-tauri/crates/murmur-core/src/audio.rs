@@ -0,0 +1,1045 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use thiserror::Error;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use crate::buffer::{AudioSnapshot, SampleBuffer};
+use crate::resample::{self, Resampler};
+
+/// Sample rate Whisper expects. All recorder output is mono at this rate.
+pub const TARGET_SAMPLE_RATE: u32 = 16_000;
+const MIN_SAMPLES: usize = 3_200; // 0.2s at 16kHz
+
+/// Minimum sample count for transcription (1s at 16kHz). Shorter clips produce hallucinations.
+pub const MIN_TRANSCRIBE_SAMPLES: usize = 16_000;
+
+/// Duration of `count` samples at `TARGET_SAMPLE_RATE`, in milliseconds.
+pub fn samples_to_ms(count: usize) -> u64 {
+    count as u64 * 1000 / TARGET_SAMPLE_RATE as u64
+}
+
+/// Energy threshold below which audio is considered silent.
+/// Typical background noise sits around 1e-5 to 1e-4; speech is usually above 1e-3.
+const SILENCE_ENERGY_THRESHOLD: f32 = 5e-5;
+
+/// Level metering block: 50ms at 16kHz.
+const LEVEL_BLOCK_SAMPLES: usize = 800;
+/// Peak at or above this is reported as clipping.
+const CLIPPING_PEAK: f32 = 0.99;
+
+/// Upper bound for the pre-roll window; it only needs to cover hotkey latency.
+const MAX_PRE_ROLL_MS: u32 = 2_000;
+
+/// A stream that delivers no audio for this long is treated as disconnected.
+const STREAM_STALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+/// Grace period for the first buffer: Bluetooth headsets and USB interfaces
+/// waking from sleep can take several seconds to start delivering audio.
+const STREAM_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// VAD analysis frame: 30ms at 16kHz.
+const VAD_FRAME_SAMPLES: usize = 480;
+/// A frame is speech when its energy exceeds the noise floor by this factor.
+const VAD_NOISE_RATIO: f32 = 3.0;
+/// Clamp for the adaptive threshold, so a dead-silent room doesn't turn every
+/// breath into speech and a noisy one doesn't swallow normal speech.
+const VAD_MIN_THRESHOLD: f32 = SILENCE_ENERGY_THRESHOLD;
+const VAD_MAX_THRESHOLD: f32 = 2e-3;
+/// Fricatives (s, f, sh) are quiet but noisy: accept them at a lower energy when
+/// the zero-crossing rate is high.
+const VAD_FRICATIVE_ZCR: f32 = 0.3;
+/// Voiced frames with fewer crossings than this are hum or rumble, not speech.
+const VAD_MIN_ZCR: f32 = 0.01;
+/// Pauses shorter than this (~0.5s) stay inside one speech span.
+const VAD_MAX_GAP_FRAMES: usize = 16;
+/// Spans shorter than this (~90ms) are clicks or pops.
+const VAD_MIN_SPEECH_FRAMES: usize = 3;
+/// Silence kept on each side of a span so word onsets and tails aren't clipped.
+const VAD_PAD_SAMPLES: usize = 3_200;
+
+/// A region of speech in 16kHz mono samples. `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct SpeechSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SpeechSpan {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn start_secs(&self) -> f64 {
+        self.start as f64 / TARGET_SAMPLE_RATE as f64
+    }
+
+    pub fn end_secs(&self) -> f64 {
+        self.end as f64 / TARGET_SAMPLE_RATE as f64
+    }
+}
+
+/// Frame-level voice activity detection on 16kHz mono samples.
+///
+/// Each 30ms frame is classified by energy against an adaptive noise floor,
+/// with zero-crossing rate used to keep fricatives and reject hum. Short
+/// pauses are bridged, clicks dropped, and each span is padded by 200ms.
+pub fn detect_speech(samples: &[f32]) -> Vec<SpeechSpan> {
+    let frames: Vec<(f32, f32)> = samples.chunks(VAD_FRAME_SAMPLES).map(frame_features).collect();
+    if frames.is_empty() {
+        return Vec::new();
+    }
+
+    // Noise floor: the 10th percentile frame energy
+    let mut energies: Vec<f32> = frames.iter().map(|&(energy, _)| energy).collect();
+    energies.sort_by(f32::total_cmp);
+    let noise_floor = energies[energies.len() / 10];
+    let threshold = (noise_floor * VAD_NOISE_RATIO).clamp(VAD_MIN_THRESHOLD, VAD_MAX_THRESHOLD);
+
+    let is_speech = |&(energy, zcr): &(f32, f32)| {
+        (energy >= threshold && zcr >= VAD_MIN_ZCR)
+            || (energy >= threshold * 0.5 && zcr >= VAD_FRICATIVE_ZCR)
+    };
+
+    // Runs of speech frames, bridging pauses up to VAD_MAX_GAP_FRAMES
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (i, frame) in frames.iter().enumerate() {
+        if !is_speech(frame) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((_, end)) if i - *end <= VAD_MAX_GAP_FRAMES => *end = i + 1,
+            _ => runs.push((i, i + 1)),
+        }
+    }
+
+    let mut spans: Vec<SpeechSpan> = Vec::new();
+    for (start, end) in runs {
+        if end - start < VAD_MIN_SPEECH_FRAMES {
+            continue;
+        }
+        let span = SpeechSpan {
+            start: (start * VAD_FRAME_SAMPLES).saturating_sub(VAD_PAD_SAMPLES),
+            end: (end * VAD_FRAME_SAMPLES + VAD_PAD_SAMPLES).min(samples.len()),
+        };
+        match spans.last_mut() {
+            Some(prev) if span.start <= prev.end => prev.end = span.end,
+            _ => spans.push(span),
+        }
+    }
+    spans
+}
+
+/// Concatenates the speech spans, dropping the silence between them.
+pub fn extract_speech(samples: &[f32], spans: &[SpeechSpan]) -> Vec<f32> {
+    let mut output = Vec::with_capacity(spans.iter().map(SpeechSpan::len).sum());
+    for span in spans {
+        output.extend_from_slice(&samples[span.start..span.end.min(samples.len())]);
+    }
+    output
+}
+
+/// Mean-square energy and zero-crossing rate of one frame. The frame mean is
+/// removed first so a DC offset doesn't hide crossings.
+fn frame_features(frame: &[f32]) -> (f32, f32) {
+    let mean = frame.iter().sum::<f32>() / frame.len() as f32;
+    let energy = frame.iter().map(|s| (s - mean) * (s - mean)).sum::<f32>() / frame.len() as f32;
+    let crossings = frame
+        .windows(2)
+        .filter(|w| ((w[0] - mean) >= 0.0) != ((w[1] - mean) >= 0.0))
+        .count();
+    (energy, crossings as f32 / frame.len() as f32)
+}
+
+/// Input level of one ~50ms block of recorded audio, linear 0.0–1.0.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct AudioLevel {
+    pub rms: f32,
+    pub peak: f32,
+    /// The block hit full scale; the input gain is too high.
+    pub clipping: bool,
+    /// After the first second, the recording so far is too quiet to pass
+    /// `is_audio_usable`, e.g. a muted or wrong microphone.
+    pub too_quiet: bool,
+}
+
+/// Turns a stream of 16kHz samples into one `AudioLevel` per block.
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    block_len: usize,
+    block_sum_sq: f32,
+    block_peak: f32,
+    total_len: usize,
+    total_sum_sq: f64,
+}
+
+impl LevelMeter {
+    /// Feeds newly recorded samples. Returns the level of the most recent
+    /// block completed by them, if any.
+    pub fn push(&mut self, samples: &[f32]) -> Option<AudioLevel> {
+        let mut level = None;
+        for &sample in samples {
+            let sq = sample * sample;
+            self.block_sum_sq += sq;
+            self.block_peak = self.block_peak.max(sample.abs());
+            self.block_len += 1;
+            self.total_sum_sq += sq as f64;
+            self.total_len += 1;
+
+            if self.block_len == LEVEL_BLOCK_SAMPLES {
+                level = Some(AudioLevel {
+                    rms: (self.block_sum_sq / self.block_len as f32).sqrt(),
+                    peak: self.block_peak,
+                    clipping: self.block_peak >= CLIPPING_PEAK,
+                    too_quiet: self.total_len >= MIN_TRANSCRIBE_SAMPLES
+                        && ((self.total_sum_sq / self.total_len as f64) as f32) < SILENCE_ENERGY_THRESHOLD,
+                });
+                self.block_len = 0;
+                self.block_sum_sq = 0.0;
+                self.block_peak = 0.0;
+            }
+        }
+        level
+    }
+}
+
+/// Receives events from a running `AudioRecorder`, on its recording thread.
+pub trait RecorderObserver: Send + 'static {
+    /// Called about every 50ms with the latest input level.
+    fn level(&mut self, _level: AudioLevel) {}
+    /// The input device disappeared mid-recording; recording has stopped.
+    fn device_lost(&mut self, _err: AudioError) {}
+}
+
+impl RecorderObserver for () {}
+
+/// An audio input device as shown in Settings.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct InputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Lists the currently connected input devices. Called again whenever the
+/// Settings window needs a fresh list, so unplugged devices drop out.
+pub fn list_input_devices() -> Vec<InputDevice> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = match host.input_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            log::error!("failed to enumerate input devices: {}", e);
+            return Vec::new();
+        }
+    };
+
+    devices
+        .filter_map(|d| d.name().ok())
+        .map(|name| InputDevice {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect()
+}
+
+/// Finds a connected input device by name. Empty name means the system default.
+fn find_input_device(host: &cpal::Host, name: &str) -> Option<cpal::Device> {
+    if name.is_empty() {
+        return host.default_input_device();
+    }
+    host.input_devices()
+        .ok()?
+        .find(|d| d.name().is_ok_and(|n| n == name))
+}
+
+/// Opens the default audio input device and returns it with its default stream config.
+/// Returns `None` if no input device is available or its config cannot be queried.
+#[cfg(target_os = "macos")]
+pub fn open_default_input() -> Option<(cpal::Device, cpal::SupportedStreamConfig)> {
+    let host = cpal::default_host();
+    let device = host.default_input_device()?;
+    let config = device.default_input_config().ok()?;
+    Some((device, config))
+}
+
+/// Returns true if the audio buffer has enough data and energy for transcription.
+/// Used to gate both local Whisper and cloud (Groq) engines.
+pub fn is_audio_usable(samples: &[f32]) -> bool {
+    is_usable(samples.len(), samples.iter().copied())
+}
+
+/// `is_audio_usable` over `len` samples from any source.
+pub(crate) fn is_usable(len: usize, samples: impl Iterator<Item = f32>) -> bool {
+    if len < MIN_TRANSCRIBE_SAMPLES {
+        log::info!("audio too short ({len} samples), skipping transcription");
+        return false;
+    }
+    let step = (len / 1000).max(1);
+    let count = len / step;
+    let energy: f32 = samples.step_by(step).map(|s| s * s).sum::<f32>() / count as f32;
+    if energy < SILENCE_ENERGY_THRESHOLD {
+        log::info!("audio energy too low ({energy:.2e}), skipping transcription");
+        return false;
+    }
+    true
+}
+
+/// Converts interleaved PCM at any rate into 16kHz mono for transcription.
+/// Used for audio that doesn't come from `AudioRecorder` (e.g. files on disk).
+pub fn prepare_samples(interleaved: &[f32], channels: u16, sample_rate: u32) -> Vec<f32> {
+    let mono: Vec<f32> = if channels > 1 {
+        interleaved
+            .chunks(channels as usize)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    } else {
+        interleaved.to_vec()
+    };
+
+    if sample_rate == TARGET_SAMPLE_RATE {
+        return mono;
+    }
+
+    resample::resample(&mono, sample_rate, TARGET_SAMPLE_RATE)
+}
+
+#[derive(Debug, Error)]
+pub enum AudioError {
+    #[error("no input device available")]
+    NoInputDevice,
+    #[error("microphone \"{0}\" is not connected")]
+    DeviceNotFound(String),
+    #[error("microphone \"{0}\" was disconnected")]
+    DeviceDisconnected(String),
+    #[error("no supported input config: {0}")]
+    NoSupportedConfig(String),
+    #[error("failed to build input stream: {0}")]
+    BuildStream(String),
+    #[error("failed to start stream: {0}")]
+    PlayStream(String),
+    #[error("failed to lock samples mutex: {0}")]
+    LockPoisoned(String),
+}
+
+impl serde::Serialize for AudioError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Audio shared between the capture callback and `AudioRecorder`.
+#[derive(Debug, Default)]
+struct CaptureBuffer {
+    recording: bool,
+    samples: SampleBuffer,
+    /// Most recent audio heard while not recording, at most `pre_roll_len`
+    /// samples. Only filled while warm; never leaves memory.
+    pre_roll: VecDeque<f32>,
+    pre_roll_len: usize,
+    /// Total samples delivered by the stream, for stall detection.
+    captured: usize,
+}
+
+impl CaptureBuffer {
+    fn push(&mut self, samples: &[f32]) {
+        self.captured += samples.len();
+        if self.recording {
+            self.samples.push(samples);
+        } else if self.pre_roll_len > 0 {
+            self.pre_roll.extend(samples);
+            let excess = self.pre_roll.len().saturating_sub(self.pre_roll_len);
+            self.pre_roll.drain(..excess);
+        }
+    }
+
+    /// Starts a recording that begins with the buffered pre-roll.
+    fn begin_recording(&mut self) {
+        self.samples.clear();
+        let (front, back) = self.pre_roll.as_slices();
+        self.samples.push(front);
+        self.samples.push(back);
+        self.pre_roll.clear();
+        self.recording = true;
+    }
+
+    fn end_recording(&mut self) -> AudioSnapshot {
+        self.recording = false;
+        self.pre_roll.clear();
+        self.samples.take()
+    }
+}
+
+pub struct AudioRecorder {
+    stop_signal: Arc<AtomicBool>,
+    /// Set by the stream thread once the device is gone, before the observer
+    /// hears of it, so a reopen from `device_lost` doesn't see a live stream.
+    stream_lost: Arc<AtomicBool>,
+    buffer: Arc<Mutex<CaptureBuffer>>,
+    thread_handle: Option<std::thread::JoinHandle<Result<(), AudioError>>>,
+    /// Device setting the open stream was requested with, and the device it resolved to.
+    requested_device: String,
+    active_device: String,
+    /// The stream stays open between recordings to fill the pre-roll.
+    warm: bool,
+}
+
+impl Default for AudioRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioRecorder {
+    pub fn new() -> Self {
+        Self {
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            stream_lost: Arc::new(AtomicBool::new(false)),
+            buffer: Arc::new(Mutex::new(CaptureBuffer::default())),
+            thread_handle: None,
+            requested_device: String::new(),
+            active_device: String::new(),
+            warm: false,
+        }
+    }
+
+    /// Opens the input device and keeps it running between recordings, holding
+    /// the last `pre_roll_ms` of audio in memory so `start` can prepend it.
+    /// Nothing is kept beyond that window, and nothing is written anywhere.
+    pub fn warm(
+        &mut self,
+        device_name: &str,
+        pre_roll_ms: u32,
+        observer: impl RecorderObserver,
+    ) -> Result<String, AudioError> {
+        let pre_roll_ms = pre_roll_ms.min(MAX_PRE_ROLL_MS);
+        let pre_roll_len = pre_roll_ms as usize * TARGET_SAMPLE_RATE as usize / 1000;
+        if self.is_warm() && self.requested_device == device_name {
+            self.lock_buffer().pre_roll_len = pre_roll_len;
+            return Ok(self.active_device.clone());
+        }
+
+        self.shutdown();
+        self.lock_buffer().pre_roll_len = pre_roll_len;
+        self.open_stream(device_name, observer)?;
+        self.warm = true;
+        log::info!("input stream kept warm with {pre_roll_ms}ms pre-roll");
+        Ok(self.active_device.clone())
+    }
+
+    /// True while a warm stream is open and filling the pre-roll.
+    pub fn is_warm(&self) -> bool {
+        self.warm
+            && !self.stream_lost.load(Ordering::SeqCst)
+            && self.thread_handle.as_ref().is_some_and(|h| !h.is_finished())
+    }
+
+    /// Closes the input stream and drops any buffered pre-roll.
+    pub fn shutdown(&mut self) {
+        self.stop_signal.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+        self.warm = false;
+
+        let mut buffer = self.lock_buffer();
+        buffer.recording = false;
+        buffer.pre_roll = VecDeque::new();
+        buffer.pre_roll_len = 0;
+    }
+
+    fn lock_buffer(&self) -> std::sync::MutexGuard<'_, CaptureBuffer> {
+        self.buffer.lock().expect("capture buffer mutex poisoned")
+    }
+
+    /// Starts recording from the input device named `device_name`, or the
+    /// system default when empty or no longer connected. Returns the name of
+    /// the device actually used.
+    ///
+    /// If the recorder is warm on the same device, the running stream is
+    /// reused and the recording begins with the pre-roll.
+    ///
+    /// `observer` is called from the recording thread with input levels and
+    /// if the device disappears mid-recording. A warm stream keeps the
+    /// observer it was opened with.
+    pub fn start(
+        &mut self,
+        device_name: &str,
+        observer: impl RecorderObserver,
+    ) -> Result<String, AudioError> {
+        if self.is_warm() && self.requested_device == device_name {
+            self.lock_buffer().begin_recording();
+            return Ok(self.active_device.clone());
+        }
+
+        self.shutdown();
+        self.lock_buffer().begin_recording();
+        self.open_stream(device_name, observer)
+    }
+
+    fn open_stream(
+        &mut self,
+        device_name: &str,
+        mut observer: impl RecorderObserver,
+    ) -> Result<String, AudioError> {
+        let host = cpal::default_host();
+        let device = match find_input_device(&host, device_name) {
+            Some(device) => device,
+            None => {
+                if !device_name.is_empty() {
+                    log::warn!("input device {device_name:?} not found, using system default");
+                }
+                host.default_input_device().ok_or(AudioError::NoInputDevice)?
+            }
+        };
+        let active_name = device.name().unwrap_or_default();
+        log::info!("opening input device {active_name:?}");
+
+        self.stop_signal.store(false, Ordering::SeqCst);
+        self.stream_lost.store(false, Ordering::SeqCst);
+
+        let stop_signal = Arc::clone(&self.stop_signal);
+        let stream_lost = Arc::clone(&self.stream_lost);
+        let buffer = Arc::clone(&self.buffer);
+        let thread_device_name = active_name.clone();
+
+        let handle = std::thread::spawn(move || -> Result<(), AudioError> {
+            // cpal streams aren't Send, so the device is looked up again on this thread
+            let host = cpal::default_host();
+            let device = find_input_device(&host, &thread_device_name)
+                .ok_or_else(|| AudioError::DeviceNotFound(thread_device_name.clone()))?;
+
+            let device_lost = Arc::new(AtomicBool::new(false));
+            let lost = Arc::clone(&device_lost);
+            let on_stream_error = move |err: cpal::StreamError| {
+                log::error!("audio stream error: {}", err);
+                if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                    lost.store(true, Ordering::SeqCst);
+                }
+            };
+
+            // Select best config: prefer mono F32, fallback to any F32, then any format
+            let supported_config = device
+                .supported_input_configs()
+                .map_err(|e| AudioError::NoSupportedConfig(e.to_string()))?
+                .find(|c| c.channels() == 1 && c.sample_format() == cpal::SampleFormat::F32)
+                .or_else(|| {
+                    device
+                        .supported_input_configs()
+                        .ok()?
+                        .find(|c| c.sample_format() == cpal::SampleFormat::F32)
+                })
+                .or_else(|| device.supported_input_configs().ok()?.next())
+                .ok_or_else(|| {
+                    AudioError::NoSupportedConfig("no compatible config found".to_string())
+                })?;
+
+            let min_rate = supported_config.min_sample_rate().0;
+            let max_rate = supported_config.max_sample_rate().0;
+
+            let device_rate = if min_rate <= TARGET_SAMPLE_RATE && TARGET_SAMPLE_RATE <= max_rate {
+                TARGET_SAMPLE_RATE
+            } else {
+                max_rate
+            };
+
+            let channels = supported_config.channels();
+            let config = cpal::StreamConfig {
+                channels,
+                sample_rate: cpal::SampleRate(device_rate),
+                buffer_size: cpal::BufferSize::Default,
+            };
+
+            // Every sample format goes through the same convert/downmix/resample path
+            let sink = CaptureSink::new(channels, device_rate, Arc::clone(&buffer));
+            let sample_format = supported_config.sample_format();
+
+            let stream = match sample_format {
+                cpal::SampleFormat::I8 => build_capture_stream::<i8>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::I16 => build_capture_stream::<i16>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::I32 => build_capture_stream::<i32>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::I64 => build_capture_stream::<i64>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::U8 => build_capture_stream::<u8>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::U16 => build_capture_stream::<u16>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::U32 => build_capture_stream::<u32>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::U64 => build_capture_stream::<u64>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::F32 => build_capture_stream::<f32>(&device, &config, sink, on_stream_error),
+                cpal::SampleFormat::F64 => build_capture_stream::<f64>(&device, &config, sink, on_stream_error),
+                _ => {
+                    return Err(AudioError::NoSupportedConfig(format!(
+                        "unsupported sample format: {:?}",
+                        sample_format
+                    )));
+                }
+            };
+            let stream = stream.map_err(|e| AudioError::BuildStream(e.to_string()))?;
+            // The buffer outlives streams, so progress is counted from here
+            let mut last_captured = buffer.lock().map(|b| b.captured).unwrap_or(0);
+            let started = std::time::Instant::now();
+            stream
+                .play()
+                .map_err(|e| AudioError::PlayStream(e.to_string()))?;
+
+            // Keep recording until stop signal. New samples are metered here
+            // rather than in the audio callback to keep the callback short.
+            // Some backends never report an unplugged device, so a stream that
+            // stops delivering audio counts as lost.
+            let mut meter = LevelMeter::default();
+            let mut last_len = 0;
+            // Unset until the first callback, which gets the longer start timeout
+            let mut last_progress: Option<std::time::Instant> = None;
+            while !stop_signal.load(Ordering::SeqCst) {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+
+                // Only copy under the lock; the callback waits on it
+                let mut fresh = None;
+                if let Ok(b) = buffer.lock() {
+                    if b.captured != last_captured {
+                        last_captured = b.captured;
+                        last_progress = Some(std::time::Instant::now());
+                    }
+                    // A new recording on a warm stream starts metering afresh
+                    if b.samples.len() < last_len {
+                        last_len = 0;
+                        meter = LevelMeter::default();
+                    }
+                    if b.samples.len() > last_len {
+                        let from = last_len.max(b.samples.start());
+                        fresh = Some(b.samples.range(from..b.samples.len()).into_owned());
+                        last_len = b.samples.len();
+                    }
+                }
+                if let Some(level) = fresh.and_then(|samples| meter.push(&samples)) {
+                    observer.level(level);
+                }
+
+                let stalled = match last_progress {
+                    Some(at) => at.elapsed() > STREAM_STALL_TIMEOUT,
+                    None => started.elapsed() > STREAM_START_TIMEOUT,
+                };
+                if device_lost.load(Ordering::SeqCst) || stalled {
+                    drop(stream);
+                    stream_lost.store(true, Ordering::SeqCst);
+                    log::error!("input device {thread_device_name:?} disconnected");
+                    observer.device_lost(AudioError::DeviceDisconnected(thread_device_name.clone()));
+                    return Err(AudioError::DeviceDisconnected(thread_device_name));
+                }
+            }
+
+            drop(stream);
+            Ok(())
+        });
+
+        self.thread_handle = Some(handle);
+        self.requested_device = device_name.to_string();
+        self.active_device = active_name.clone();
+        Ok(active_name)
+    }
+
+    /// Returns the audio recorded so far without stopping recording. Only
+    /// the last partial chunk is copied, so this is cheap to call often.
+    pub fn snapshot(&self) -> Result<AudioSnapshot, AudioError> {
+        self.buffer
+            .lock()
+            .map(|b| b.samples.snapshot())
+            .map_err(|e| AudioError::LockPoisoned(e.to_string()))
+    }
+
+    /// Frees recorded audio before sample `upto` once nothing needs it,
+    /// e.g. after it was transcribed. Later snapshots start there, rounded
+    /// down to a chunk.
+    pub fn release(&self, upto: usize) {
+        self.lock_buffer().samples.release(upto);
+    }
+
+    /// Ends the recording and returns its samples. A warm stream stays open
+    /// and goes back to filling the pre-roll.
+    pub fn stop(&mut self) -> AudioSnapshot {
+        if !self.is_warm() {
+            self.shutdown();
+        }
+        let samples = self.lock_buffer().end_recording();
+
+        // Short recording protection
+        if samples.len() < MIN_SAMPLES {
+            return AudioSnapshot::default();
+        }
+
+        samples
+    }
+}
+
+impl Drop for AudioRecorder {
+    fn drop(&mut self) {
+        // A warm stream would otherwise keep the microphone open forever
+        self.shutdown();
+    }
+}
+
+/// Converts captured frames of any sample format to 16kHz mono and appends
+/// them to the shared capture buffer. Owned by the cpal data callback.
+struct CaptureSink {
+    channels: u16,
+    resampler: Option<Resampler>,
+    /// Reusable buffers to avoid allocation in the audio callback
+    mono: Vec<f32>,
+    resampled: Vec<f32>,
+    /// Samples not yet handed to `buffer` because a reader held its lock.
+    /// They go in ahead of the next block, so the callback never waits; a
+    /// block pending when a recording stops (one callback, ~10ms) misses it.
+    pending: Vec<f32>,
+    buffer: Arc<Mutex<CaptureBuffer>>,
+}
+
+impl CaptureSink {
+    fn new(channels: u16, device_rate: u32, buffer: Arc<Mutex<CaptureBuffer>>) -> Self {
+        Self {
+            channels,
+            // Filter state carries across callbacks, so chunk edges stay continuous
+            resampler: (device_rate != TARGET_SAMPLE_RATE)
+                .then(|| Resampler::new(device_rate, TARGET_SAMPLE_RATE)),
+            mono: Vec::with_capacity(4096),
+            resampled: Vec::with_capacity(4096),
+            pending: Vec::with_capacity(4096),
+            buffer,
+        }
+    }
+
+    fn push<T>(&mut self, data: &[T])
+    where
+        T: cpal::Sample,
+        f32: cpal::FromSample<T>,
+    {
+        self.mono.clear();
+        downmix_into(data, self.channels, &mut self.mono);
+
+        let output = match self.resampler.as_mut() {
+            Some(resampler) => {
+                self.resampled.clear();
+                resampler.process(&self.mono, &mut self.resampled);
+                &self.resampled
+            }
+            None => &self.mono,
+        };
+        match self.buffer.try_lock() {
+            Ok(mut b) => {
+                if !self.pending.is_empty() {
+                    b.push(&self.pending);
+                    self.pending.clear();
+                }
+                b.push(output);
+            }
+            Err(TryLockError::WouldBlock) => self.pending.extend_from_slice(output),
+            Err(TryLockError::Poisoned(_)) => {}
+        }
+    }
+}
+
+fn build_capture_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut sink: CaptureSink,
+    on_error: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| sink.push(data),
+        on_error,
+        None,
+    )
+}
+
+/// Converts interleaved samples of any cpal format to mono f32 in -1.0..1.0,
+/// averaging channels.
+fn downmix_into<T>(data: &[T], channels: u16, output: &mut Vec<f32>)
+where
+    T: cpal::Sample,
+    f32: cpal::FromSample<T>,
+{
+    if channels <= 1 {
+        output.extend(data.iter().map(|&s| s.to_sample::<f32>()));
+        return;
+    }
+    for frame in data.chunks(channels as usize) {
+        let sum: f32 = frame.iter().map(|&s| s.to_sample::<f32>()).sum();
+        output.push(sum / frame.len() as f32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_samples_downmix_and_resample() {
+        // Stereo 32kHz: left=1.0, right=0.0 → mono 0.5 at half the frame count
+        let interleaved: Vec<f32> = (0..3200).flat_map(|_| [1.0, 0.0]).collect();
+        let output = prepare_samples(&interleaved, 2, 32_000);
+        assert_eq!(output.len(), 1600);
+        // The resampler's filter ramps in and out at the buffer edges
+        assert!(output[50..1550].iter().all(|s| (s - 0.5).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_prepare_samples_passthrough() {
+        let input = vec![0.1, -0.2, 0.3];
+        assert_eq!(prepare_samples(&input, 1, TARGET_SAMPLE_RATE), input);
+    }
+
+    /// Low-level deterministic noise, like a quiet room.
+    fn room_noise(len: usize) -> Vec<f32> {
+        let mut seed: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((seed >> 8) as f32 / (1 << 24) as f32 - 0.5) * 0.002
+            })
+            .collect()
+    }
+
+    /// Voiced-speech stand-in: 200Hz tone with harmonics.
+    fn add_tone(samples: &mut [f32], start_secs: f32, end_secs: f32, freq: f32) {
+        let rate = TARGET_SAMPLE_RATE as f32;
+        let start = (start_secs * rate) as usize;
+        let end = (end_secs * rate) as usize;
+        for (i, s) in samples[start..end].iter_mut().enumerate() {
+            let t = i as f32 / rate;
+            let phase = 2.0 * std::f32::consts::PI * freq * t;
+            *s += 0.2 * phase.sin() + 0.1 * (2.0 * phase).sin() + 0.05 * (3.0 * phase).sin();
+        }
+    }
+
+    #[test]
+    fn test_detect_speech_silence_has_no_spans() {
+        assert!(detect_speech(&room_noise(48_000)).is_empty());
+        assert!(detect_speech(&vec![0.0; 48_000]).is_empty());
+        assert!(detect_speech(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_speech_trims_leading_and_trailing_silence() {
+        let mut samples = room_noise(64_000);
+        add_tone(&mut samples, 1.5, 2.5, 200.0);
+        let spans = detect_speech(&samples);
+        assert_eq!(spans.len(), 1);
+        // Padded by 200ms, snapped to 30ms frames
+        assert!((spans[0].start_secs() - 1.3).abs() < 0.05, "{:?}", spans[0]);
+        assert!((spans[0].end_secs() - 2.7).abs() < 0.05, "{:?}", spans[0]);
+    }
+
+    #[test]
+    fn test_detect_speech_splits_on_long_pause() {
+        let mut samples = room_noise(96_000);
+        add_tone(&mut samples, 0.5, 1.5, 200.0);
+        add_tone(&mut samples, 4.0, 5.0, 200.0);
+        let spans = detect_speech(&samples);
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].end < spans[1].start);
+    }
+
+    #[test]
+    fn test_detect_speech_bridges_short_pause() {
+        let mut samples = room_noise(64_000);
+        add_tone(&mut samples, 0.5, 1.5, 200.0);
+        add_tone(&mut samples, 1.8, 2.8, 200.0);
+        assert_eq!(detect_speech(&samples).len(), 1);
+    }
+
+    #[test]
+    fn test_detect_speech_ignores_clicks_and_hum() {
+        let mut samples = room_noise(48_000);
+        add_tone(&mut samples, 1.0, 1.03, 200.0);
+        assert!(detect_speech(&samples).is_empty());
+
+        // 40Hz mains-style hum: loud but too few zero crossings
+        let mut hum = room_noise(48_000);
+        add_tone(&mut hum, 0.0, 3.0, 40.0);
+        assert!(detect_speech(&hum).is_empty());
+    }
+
+    #[test]
+    fn test_detect_speech_keeps_fricatives() {
+        // "s"-like burst: low energy, high zero-crossing rate
+        let mut samples = vec![0.0; 48_000];
+        for (i, s) in samples[16_000..24_000].iter_mut().enumerate() {
+            *s = if i % 2 == 0 { 0.01 } else { -0.01 };
+        }
+        assert_eq!(detect_speech(&samples).len(), 1);
+    }
+
+    #[test]
+    fn test_extract_speech_concatenates_spans() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let spans = [SpeechSpan { start: 1, end: 3 }, SpeechSpan { start: 6, end: 8 }];
+        assert_eq!(extract_speech(&samples, &spans), vec![1.0, 2.0, 6.0, 7.0]);
+    }
+
+    #[test]
+    fn test_level_meter_reports_per_block() {
+        let mut meter = LevelMeter::default();
+        assert!(meter.push(&[0.5; 799]).is_none());
+        let level = meter.push(&[0.5; 801]).unwrap();
+        assert!((level.rms - 0.5).abs() < 1e-4);
+        assert!((level.peak - 0.5).abs() < 1e-6);
+        assert!(!level.clipping);
+        // 1600 samples is under a second, too early to call it quiet
+        assert!(!level.too_quiet);
+    }
+
+    #[test]
+    fn test_level_meter_detects_clipping() {
+        let mut meter = LevelMeter::default();
+        let mut block = vec![0.1; LEVEL_BLOCK_SAMPLES];
+        block[10] = -1.0;
+        let level = meter.push(&block).unwrap();
+        assert!(level.clipping);
+        assert_eq!(level.peak, 1.0);
+    }
+
+    #[test]
+    fn test_level_meter_too_quiet_matches_is_audio_usable() {
+        let mut meter = LevelMeter::default();
+        let quiet = vec![0.001; MIN_TRANSCRIBE_SAMPLES];
+        assert!(meter.push(&quiet).unwrap().too_quiet);
+        assert!(!is_audio_usable(&quiet));
+
+        let mut meter = LevelMeter::default();
+        let speech = vec![0.05; MIN_TRANSCRIBE_SAMPLES];
+        assert!(!meter.push(&speech).unwrap().too_quiet);
+        assert!(is_audio_usable(&speech));
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-3, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn downmix<T>(data: &[T], channels: u16) -> Vec<f32>
+    where
+        T: cpal::Sample,
+        f32: cpal::FromSample<T>,
+    {
+        let mut output = Vec::new();
+        downmix_into(data, channels, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_downmix_signed_formats() {
+        assert_close(&downmix(&[0i8, i8::MIN, 64], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[0i16, i16::MIN, 16_384], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[0i32, i32::MIN, 1 << 30], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[0i64, i64::MIN, 1 << 62], 1), &[0.0, -1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_downmix_unsigned_formats() {
+        // Unsigned formats are offset: the midpoint is silence
+        assert_close(&downmix(&[128u8, 0, 192], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[32_768u16, 0, 49_152], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[1u32 << 31, 0, 3 << 30], 1), &[0.0, -1.0, 0.5]);
+        assert_close(&downmix(&[1u64 << 63, 0, 3 << 62], 1), &[0.0, -1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_downmix_float_formats() {
+        assert_close(&downmix(&[0.25f32, -0.5], 1), &[0.25, -0.5]);
+        assert_close(&downmix(&[0.25f64, -0.5], 1), &[0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        // Stereo i32: (1.0 + 0.0) / 2, (-0.5 + -0.5) / 2
+        let data = [i32::MAX, 0, -(1 << 30), -(1 << 30)];
+        assert_close(&downmix(&data, 2), &[0.5, -0.5]);
+        // Quad u16
+        let data = [65_535u16, 32_768, 32_768, 32_768];
+        assert_close(&downmix(&data, 4), &[0.25]);
+    }
+
+    fn recording_buffer() -> Arc<Mutex<CaptureBuffer>> {
+        let mut buffer = CaptureBuffer::default();
+        buffer.begin_recording();
+        Arc::new(Mutex::new(buffer))
+    }
+
+    #[test]
+    fn test_capture_sink_resamples_across_callbacks() {
+        let buffer = recording_buffer();
+        let mut sink = CaptureSink::new(2, 48_000, Arc::clone(&buffer));
+        // 1s of stereo u16 at 48kHz in 10ms callbacks
+        let callback = vec![49_152u16; 480 * 2];
+        for _ in 0..100 {
+            sink.push(&callback);
+        }
+
+        let samples = buffer.lock().unwrap().samples.snapshot().to_vec();
+        // Minus the resampler's look-ahead, which stays buffered until the stream ends
+        assert!((15_900..=16_000).contains(&samples.len()), "{}", samples.len());
+        assert!(samples[100..].iter().all(|s| (s - 0.5).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_capture_sink_passthrough_at_target_rate() {
+        let buffer = recording_buffer();
+        let mut sink = CaptureSink::new(1, TARGET_SAMPLE_RATE, Arc::clone(&buffer));
+        sink.push(&[0i16, 16_384, i16::MIN]);
+        assert_close(&buffer.lock().unwrap().samples.snapshot().to_vec(), &[0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_capture_sink_does_not_wait_for_readers() {
+        let buffer = recording_buffer();
+        let mut sink = CaptureSink::new(1, TARGET_SAMPLE_RATE, Arc::clone(&buffer));
+        {
+            // A reader holds the lock: the block is kept, not waited on
+            let reader = buffer.lock().unwrap();
+            sink.push(&[0.25f32, 0.5]);
+            assert!(reader.samples.is_empty());
+        }
+        sink.push(&[0.75f32]);
+        assert_close(&buffer.lock().unwrap().samples.snapshot().to_vec(), &[0.25, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn test_capture_buffer_discards_audio_without_pre_roll() {
+        let mut buffer = CaptureBuffer::default();
+        buffer.push(&[0.1; 800]);
+        assert!(buffer.pre_roll.is_empty());
+        buffer.begin_recording();
+        assert!(buffer.samples.is_empty());
+    }
+
+    #[test]
+    fn test_capture_buffer_pre_roll_keeps_latest_audio() {
+        let mut buffer = CaptureBuffer {
+            pre_roll_len: 4,
+            ..CaptureBuffer::default()
+        };
+        buffer.push(&[1.0, 2.0, 3.0]);
+        buffer.push(&[4.0, 5.0, 6.0]);
+        assert_eq!(buffer.pre_roll, [3.0, 4.0, 5.0, 6.0]);
+
+        buffer.begin_recording();
+        buffer.push(&[7.0]);
+        assert_eq!(buffer.end_recording().to_vec(), vec![3.0, 4.0, 5.0, 6.0, 7.0]);
+
+        // Back to pre-roll only; the previous recording isn't carried over
+        assert!(buffer.samples.is_empty());
+        assert!(buffer.pre_roll.is_empty());
+        buffer.push(&[8.0]);
+        assert_eq!(buffer.pre_roll, [8.0]);
+    }
+}
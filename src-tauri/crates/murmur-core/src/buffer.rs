@@ -0,0 +1,306 @@
+// Append-only sample storage shared by the capture callback, live preview
+// and the final transcription. Samples fill fixed-size chunks; full chunks
+// are frozen behind an `Arc`, so readers snapshot the recording by cloning
+// pointers instead of copying audio, and the callback only holds the lock
+// long enough to append.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::audio;
+
+/// Samples per chunk (1s at 16kHz). A snapshot copies at most this many.
+pub const CHUNK_SAMPLES: usize = 16_000;
+
+/// Growing recording. Everything but the last, partially filled chunk is
+/// immutable. Samples are indexed from the start of the recording, even
+/// after `release` dropped the oldest ones.
+#[derive(Debug, Default)]
+pub struct SampleBuffer {
+    sealed: Vec<Arc<Vec<f32>>>,
+    tail: Vec<f32>,
+    /// Index of the first sample held.
+    start: usize,
+    len: usize,
+}
+
+impl SampleBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Samples recorded, including released ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Index of the first sample still held.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Append samples, sealing the tail each time it fills a chunk.
+    pub fn push(&mut self, mut samples: &[f32]) {
+        self.len += samples.len();
+        while !samples.is_empty() {
+            if self.tail.capacity() == 0 {
+                self.tail.reserve_exact(CHUNK_SAMPLES);
+            }
+            let n = samples.len().min(CHUNK_SAMPLES - self.tail.len());
+            self.tail.extend_from_slice(&samples[..n]);
+            samples = &samples[n..];
+            if self.tail.len() == CHUNK_SAMPLES {
+                // Moves the allocation into the Arc; no samples are copied
+                self.sealed.push(Arc::new(std::mem::take(&mut self.tail)));
+            }
+        }
+    }
+
+    /// Samples in `range`, borrowed when it lies within one chunk.
+    /// Panics if `range` is out of bounds, like slicing.
+    pub fn range(&self, range: Range<usize>) -> Cow<'_, [f32]> {
+        let chunks = self.sealed.iter().map(|c| c.as_slice()).chain([self.tail.as_slice()]);
+        slice_chunks(chunks, self.start, self.len, range)
+    }
+
+    /// The samples so far. Shares the sealed chunks and copies the tail.
+    pub fn snapshot(&self) -> AudioSnapshot {
+        let mut chunks = self.sealed.clone();
+        if !self.tail.is_empty() {
+            chunks.push(Arc::new(self.tail.clone()));
+        }
+        AudioSnapshot {
+            chunks,
+            start: self.start,
+            len: self.len,
+        }
+    }
+
+    /// Move the samples out, leaving the buffer empty. Copies nothing.
+    pub fn take(&mut self) -> AudioSnapshot {
+        let mut chunks = std::mem::take(&mut self.sealed);
+        if !self.tail.is_empty() {
+            chunks.push(Arc::new(std::mem::take(&mut self.tail)));
+        }
+        AudioSnapshot {
+            chunks,
+            start: std::mem::take(&mut self.start),
+            len: std::mem::take(&mut self.len),
+        }
+    }
+
+    /// Drop the sealed chunks that end at or before sample `upto`, e.g.
+    /// audio already transcribed. Indices of later samples don't change.
+    pub fn release(&mut self, upto: usize) {
+        let count = (upto.saturating_sub(self.start) / CHUNK_SAMPLES).min(self.sealed.len());
+        self.sealed.drain(..count);
+        self.start += count * CHUNK_SAMPLES;
+    }
+
+    pub fn clear(&mut self) {
+        self.sealed.clear();
+        self.tail.clear();
+        self.start = 0;
+        self.len = 0;
+    }
+}
+
+/// Immutable view of a recording: cheap to clone and to send across
+/// threads. Samples may span several chunks; `range` and `to_vec` make
+/// them contiguous where an API needs a slice.
+#[derive(Debug, Clone, Default)]
+pub struct AudioSnapshot {
+    chunks: Vec<Arc<Vec<f32>>>,
+    start: usize,
+    len: usize,
+}
+
+impl AudioSnapshot {
+    /// Samples recorded, including released ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Index of the first sample held; earlier ones were released.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = f32> + '_ {
+        self.chunks.iter().flat_map(|c| c.iter().copied())
+    }
+
+    /// Samples in `range`, borrowed when it lies within one chunk.
+    /// Panics if `range` is out of bounds, like slicing.
+    pub fn range(&self, range: Range<usize>) -> Cow<'_, [f32]> {
+        slice_chunks(self.chunks.iter().map(|c| c.as_slice()), self.start, self.len, range)
+    }
+
+    /// All samples held, contiguous.
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.range(self.start..self.len).into_owned()
+    }
+
+    /// Enough data and energy to transcribe; see `audio::is_audio_usable`.
+    pub fn is_usable(&self) -> bool {
+        audio::is_usable(self.len - self.start, self.iter())
+    }
+}
+
+impl From<Vec<f32>> for AudioSnapshot {
+    fn from(samples: Vec<f32>) -> Self {
+        let len = samples.len();
+        let chunks = if samples.is_empty() { Vec::new() } else { vec![Arc::new(samples)] };
+        Self { chunks, start: 0, len }
+    }
+}
+
+/// Samples in `range` of the concatenated `chunks`, which hold samples
+/// `start..len`.
+fn slice_chunks<'c>(
+    chunks: impl Iterator<Item = &'c [f32]>,
+    start: usize,
+    len: usize,
+    range: Range<usize>,
+) -> Cow<'c, [f32]> {
+    assert!(
+        start <= range.start && range.start <= range.end && range.end <= len,
+        "range {range:?} out of bounds for samples {start}..{len}"
+    );
+    let mut parts = Vec::new();
+    let mut offset = start;
+    for chunk in chunks {
+        let (start, end) = (offset, offset + chunk.len());
+        offset = end;
+        if end <= range.start {
+            continue;
+        }
+        if start >= range.end {
+            break;
+        }
+        parts.push(&chunk[range.start.max(start) - start..range.end.min(end) - start]);
+    }
+    match parts.as_slice() {
+        [] => Cow::Borrowed(&[]),
+        [part] => Cow::Borrowed(*part),
+        parts => Cow::Owned(parts.concat()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(n: usize) -> Vec<f32> {
+        (0..n).map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn test_push_seals_full_chunks() {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(&ramp(CHUNK_SAMPLES - 10));
+        assert!(buffer.sealed.is_empty());
+        buffer.push(&ramp(CHUNK_SAMPLES * 2));
+        assert_eq!(buffer.sealed.len(), 2);
+        assert_eq!(buffer.tail.len(), CHUNK_SAMPLES - 10);
+        assert_eq!(buffer.len(), CHUNK_SAMPLES * 3 - 10);
+        assert!(buffer.sealed.iter().all(|c| c.len() == CHUNK_SAMPLES));
+    }
+
+    #[test]
+    fn test_snapshot_shares_sealed_chunks() {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(&ramp(CHUNK_SAMPLES + 5));
+        let snapshot = buffer.snapshot();
+        assert!(Arc::ptr_eq(&snapshot.chunks[0], &buffer.sealed[0]));
+        assert_eq!(snapshot.len(), CHUNK_SAMPLES + 5);
+
+        // Later pushes don't change an earlier snapshot
+        buffer.push(&[1.0; 100]);
+        assert_eq!(snapshot.len(), CHUNK_SAMPLES + 5);
+        assert_eq!(snapshot.to_vec(), ramp(CHUNK_SAMPLES + 5));
+    }
+
+    #[test]
+    fn test_range_across_chunks() {
+        let mut buffer = SampleBuffer::new();
+        let samples = ramp(CHUNK_SAMPLES * 3 + 100);
+        for block in samples.chunks(480) {
+            buffer.push(block);
+        }
+        let snapshot = buffer.snapshot();
+        assert_eq!(snapshot.to_vec(), samples);
+
+        let within = snapshot.range(CHUNK_SAMPLES + 1..CHUNK_SAMPLES + 50);
+        assert!(matches!(within, Cow::Borrowed(_)));
+        assert_eq!(*within, samples[CHUNK_SAMPLES + 1..CHUNK_SAMPLES + 50]);
+
+        let across = snapshot.range(CHUNK_SAMPLES - 3..CHUNK_SAMPLES * 3 + 2);
+        assert_eq!(*across, samples[CHUNK_SAMPLES - 3..CHUNK_SAMPLES * 3 + 2]);
+        assert!(snapshot.range(7..7).is_empty());
+        assert_eq!(*buffer.range(CHUNK_SAMPLES * 3 - 1..CHUNK_SAMPLES * 3 + 3), samples[CHUNK_SAMPLES * 3 - 1..CHUNK_SAMPLES * 3 + 3]);
+    }
+
+    #[test]
+    fn test_take_empties_buffer() {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(&ramp(CHUNK_SAMPLES + 1));
+        let taken = buffer.take();
+        assert_eq!(taken.to_vec(), ramp(CHUNK_SAMPLES + 1));
+        assert!(buffer.is_empty());
+        assert!(buffer.take().is_empty());
+
+        buffer.push(&[1.0, 2.0]);
+        assert_eq!(buffer.snapshot().to_vec(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_release_keeps_indices() {
+        let mut buffer = SampleBuffer::new();
+        let samples = ramp(CHUNK_SAMPLES * 3 + 10);
+        buffer.push(&samples);
+
+        // Only whole chunks before the cut are dropped
+        buffer.release(CHUNK_SAMPLES * 2 - 1);
+        assert_eq!(buffer.start(), CHUNK_SAMPLES);
+        assert_eq!(buffer.sealed.len(), 2);
+        assert_eq!(buffer.len(), samples.len());
+
+        let snapshot = buffer.snapshot();
+        assert_eq!(snapshot.start(), CHUNK_SAMPLES);
+        assert_eq!(snapshot.to_vec(), samples[CHUNK_SAMPLES..]);
+        assert_eq!(*snapshot.range(CHUNK_SAMPLES * 2 - 5..CHUNK_SAMPLES * 2 + 5), samples[CHUNK_SAMPLES * 2 - 5..CHUNK_SAMPLES * 2 + 5]);
+
+        // The partial tail is never released
+        buffer.release(samples.len());
+        assert_eq!(buffer.start(), CHUNK_SAMPLES * 3);
+        assert_eq!(buffer.snapshot().to_vec(), samples[CHUNK_SAMPLES * 3..]);
+        assert_eq!(buffer.take().start(), CHUNK_SAMPLES * 3);
+        assert_eq!(buffer.start(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_range_before_start_panics() {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(&ramp(CHUNK_SAMPLES * 2));
+        buffer.release(CHUNK_SAMPLES);
+        buffer.snapshot().range(CHUNK_SAMPLES - 1..CHUNK_SAMPLES + 1);
+    }
+
+    #[test]
+    fn test_snapshot_usable() {
+        assert!(AudioSnapshot::from(vec![0.1; audio::MIN_TRANSCRIBE_SAMPLES]).is_usable());
+        assert!(!AudioSnapshot::from(vec![0.0; audio::MIN_TRANSCRIBE_SAMPLES]).is_usable());
+        assert!(!AudioSnapshot::from(vec![0.1; 100]).is_usable());
+    }
+}
@@ -0,0 +1,328 @@
+use thiserror::Error;
+
+use crate::audio;
+use crate::llm::{self, LlmError};
+use crate::settings::Settings;
+use crate::transcript::{TranscribeOptions, Transcript};
+use crate::whisper::{TranscriptionEngine, WhisperError};
+
+const GROQ_TRANSCRIPTION_URL: &str = "https://api.groq.com/openai/v1/audio/transcriptions";
+const GROQ_TRANSCRIPTION_MODEL: &str = "whisper-large-v3-turbo";
+/// Path appended to a bare base URL for OpenAI-compatible servers.
+const TRANSCRIPTION_PATH: &str = "/v1/audio/transcriptions";
+/// Groq's Whisper API limits the prompt to 896 characters; other servers
+/// accept at least as much.
+const MAX_PROMPT_BYTES: usize = 896;
+
+#[derive(Debug, Error)]
+pub enum EngineError {
+    #[error(transparent)]
+    Whisper(#[from] WhisperError),
+    #[error("transcription request failed: {0}")]
+    Remote(#[from] LlmError),
+}
+
+impl serde::Serialize for EngineError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Speech-to-text backend. All methods are sync — remote implementations
+/// block on their own `tokio::runtime::Runtime`, like `TextEnhancer`.
+pub trait SpeechEngine: Send + Sync {
+    fn name(&self) -> &str;
+    /// True when audio never leaves the machine.
+    fn is_local(&self) -> bool;
+    /// True when re-running on growing partial audio is cheap enough for
+    /// live preview.
+    fn supports_streaming(&self) -> bool;
+    /// Transcribe 16kHz mono samples.
+    fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError>;
+}
+
+impl SpeechEngine for TranscriptionEngine {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+        Ok(TranscriptionEngine::transcribe(self, samples, options)?)
+    }
+}
+
+/// OpenAI-compatible `/v1/audio/transcriptions` endpoint. Covers Groq and
+/// self-hosted servers such as faster-whisper-server.
+pub struct RemoteEngine {
+    pub api_url: String,
+    api_key: String,
+    model: String,
+    local: bool,
+    engine_name: String,
+}
+
+impl RemoteEngine {
+    pub fn groq(api_key: &str) -> Self {
+        Self {
+            api_url: GROQ_TRANSCRIPTION_URL.to_string(),
+            api_key: api_key.to_string(),
+            model: GROQ_TRANSCRIPTION_MODEL.to_string(),
+            local: false,
+            engine_name: "groq".to_string(),
+        }
+    }
+
+    /// `url` may be a base URL ("http://localhost:8000") or the full
+    /// transcription endpoint. Servers on loopback count as local.
+    pub fn custom(url: &str, api_key: &str, model: &str) -> Self {
+        let url = url.trim().trim_end_matches('/');
+        let api_url = if url.ends_with("/audio/transcriptions") {
+            url.to_string()
+        } else {
+            format!("{url}{TRANSCRIPTION_PATH}")
+        };
+        Self {
+            local: is_loopback_url(&api_url),
+            api_url,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            engine_name: "custom".to_string(),
+        }
+    }
+
+    async fn request(&self, samples: &[f32], language: &str, initial_prompt: &str) -> Result<String, LlmError> {
+        let wav_bytes = llm::encode_wav(samples)?;
+
+        let file_part = reqwest::multipart::Part::bytes(wav_bytes)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| LlmError::Api(e.to_string()))?;
+
+        let mut form = reqwest::multipart::Form::new()
+            .text("model", self.model.clone())
+            .text("response_format", "json".to_string())
+            .part("file", file_part);
+
+        if language != "auto" {
+            form = form.text("language", language.to_string());
+        }
+
+        if !initial_prompt.is_empty() {
+            form = form.text("prompt", truncate_prompt(initial_prompt).to_string());
+        }
+
+        let client = reqwest::Client::new();
+        let mut req = client.post(&self.api_url).multipart(form);
+        if !self.api_key.is_empty() {
+            req = req.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        let response = req.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body_text = response
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("(failed to read body: {e})"));
+            return Err(LlmError::Api(format!("{status}: {body_text}")));
+        }
+
+        let result: llm::TranscriptionResponse = response.json().await?;
+        Ok(result.text.trim().to_string())
+    }
+}
+
+impl SpeechEngine for RemoteEngine {
+    fn name(&self) -> &str {
+        &self.engine_name
+    }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn supports_streaming(&self) -> bool {
+        // One upload per preview tick is too slow and, for Groq, billed
+        false
+    }
+
+    /// The OpenAI-style JSON response has no timing, so the transcript is a
+    /// single segment spanning the clip.
+    fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| LlmError::Api(format!("failed to create runtime: {e}")))?;
+        let text = rt.block_on(self.request(samples, options.language, options.initial_prompt))?;
+        Ok(Transcript::from_text(&text, audio::samples_to_ms(samples.len())))
+    }
+}
+
+/// Creates the remote engine selected by `settings.engine`.
+/// Returns None for the local engine, or if required config is missing —
+/// callers then fall back to local Whisper.
+pub fn create_remote_engine(settings: &Settings) -> Option<Box<dyn SpeechEngine>> {
+    match settings.engine.as_str() {
+        "groq" => {
+            if settings.groq_api_key.is_empty() {
+                return None;
+            }
+            Some(Box::new(RemoteEngine::groq(&settings.groq_api_key)))
+        }
+        "custom" => {
+            if settings.custom_engine_url.trim().is_empty() {
+                return None;
+            }
+            Some(Box::new(RemoteEngine::custom(
+                &settings.custom_engine_url,
+                &settings.custom_engine_key,
+                &settings.custom_engine_model,
+            )))
+        }
+        _ => None,
+    }
+}
+
+/// Cut the prompt to `MAX_PROMPT_BYTES` at a char boundary.
+fn truncate_prompt(prompt: &str) -> &str {
+    if prompt.len() <= MAX_PROMPT_BYTES {
+        return prompt;
+    }
+    let mut end = MAX_PROMPT_BYTES;
+    while !prompt.is_char_boundary(end) {
+        end -= 1;
+    }
+    &prompt[..end]
+}
+
+fn is_loopback_url(url: &str) -> bool {
+    let Some(rest) = url.split("://").nth(1) else {
+        return false;
+    };
+    let authority = rest.split('/').next().unwrap_or("");
+    let host = authority.rsplit('@').next().unwrap_or("");
+    let host = if let Some(v6) = host.strip_prefix('[') {
+        v6.split(']').next().unwrap_or("")
+    } else {
+        host.split(':').next().unwrap_or("")
+    };
+    host.eq_ignore_ascii_case("localhost") || host == "::1" || host.starts_with("127.")
+}
+
+/// Scripted engine for pipeline tests: returns a fixed text and records the
+/// length of every clip it was given.
+#[cfg(test)]
+pub(crate) struct MockEngine {
+    pub text: String,
+    pub local: bool,
+    pub calls: std::sync::Mutex<Vec<usize>>,
+    /// `options.translate` of each call.
+    pub translate_calls: std::sync::Mutex<Vec<bool>>,
+}
+
+#[cfg(test)]
+impl MockEngine {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            local: true,
+            calls: std::sync::Mutex::new(Vec::new()),
+            translate_calls: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl SpeechEngine for MockEngine {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+        self.calls.lock().unwrap().push(samples.len());
+        self.translate_calls.lock().unwrap().push(options.translate);
+        Ok(Transcript::from_text(&self.text, audio::samples_to_ms(samples.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_remote_engine_local() {
+        assert!(create_remote_engine(&Settings::default()).is_none());
+    }
+
+    #[test]
+    fn test_create_remote_engine_groq() {
+        let mut s = Settings {
+            engine: "groq".to_string(),
+            ..Default::default()
+        };
+        assert!(create_remote_engine(&s).is_none());
+        s.groq_api_key = "gsk_test".to_string();
+        let engine = create_remote_engine(&s).unwrap();
+        assert_eq!(engine.name(), "groq");
+        assert!(!engine.is_local());
+        assert!(!engine.supports_streaming());
+    }
+
+    #[test]
+    fn test_create_remote_engine_custom() {
+        let mut s = Settings {
+            engine: "custom".to_string(),
+            ..Default::default()
+        };
+        assert!(create_remote_engine(&s).is_none());
+        s.custom_engine_url = "http://localhost:8000".to_string();
+        let engine = create_remote_engine(&s).unwrap();
+        assert_eq!(engine.name(), "custom");
+        assert!(engine.is_local());
+    }
+
+    #[test]
+    fn test_custom_endpoint_url() {
+        let base = RemoteEngine::custom("http://localhost:8000/", "", "whisper-1");
+        assert_eq!(base.api_url, "http://localhost:8000/v1/audio/transcriptions");
+        let full = RemoteEngine::custom("https://stt.example.com/v1/audio/transcriptions", "", "whisper-1");
+        assert_eq!(full.api_url, "https://stt.example.com/v1/audio/transcriptions");
+        assert!(!full.is_local());
+    }
+
+    #[test]
+    fn test_is_loopback_url() {
+        assert!(is_loopback_url("http://127.0.0.1:9000/v1"));
+        assert!(is_loopback_url("http://[::1]:8000"));
+        assert!(is_loopback_url("http://user@LOCALHOST"));
+        assert!(!is_loopback_url("http://localhost.example.com"));
+        assert!(!is_loopback_url("https://192.168.1.10:8000"));
+        assert!(!is_loopback_url("localhost:8000"));
+    }
+
+    #[test]
+    fn test_truncate_prompt_char_boundary() {
+        let prompt = "語".repeat(400); // 1200 bytes
+        let truncated = truncate_prompt(&prompt);
+        assert!(truncated.len() <= MAX_PROMPT_BYTES);
+        assert_eq!(truncated.chars().count(), MAX_PROMPT_BYTES / 3);
+        assert_eq!(truncate_prompt("short"), "short");
+    }
+}
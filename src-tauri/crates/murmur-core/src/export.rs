@@ -0,0 +1,721 @@
+// Renders a timed transcript as SRT or WebVTT subtitles, versioned JSON or
+// Markdown, and parses those files back. Subtitle lines are wrapped by
+// display width: CJK characters count double and may break between any two
+// of them, except before closing or after opening punctuation.
+
+use thiserror::Error;
+
+use crate::transcript::{is_cjk, DetectedLanguage, Segment, Transcript, Word};
+
+/// Widest subtitle line, in columns; a CJK character takes two.
+pub const MAX_LINE_WIDTH: usize = 42;
+/// Lines per subtitle cue. Longer segments are split across cues.
+pub const MAX_CUE_LINES: usize = 2;
+/// Version of the JSON schema written by `render`.
+pub const JSON_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// SubRip subtitles
+    Srt,
+    /// WebVTT subtitles
+    Vtt,
+    /// Versioned JSON with segment and word timing
+    Json,
+    /// Markdown, one timestamped paragraph per segment
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [Self::Srt, Self::Vtt, Self::Json, Self::Markdown];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.to_ascii_lowercase();
+        match extension.as_str() {
+            "markdown" => Some(Self::Markdown),
+            _ => Self::ALL.into_iter().find(|f| f.extension() == extension),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("invalid JSON transcript: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported JSON transcript version {0}")]
+    Version(u32),
+}
+
+/// The transcript to export for the final `text`. Segment timing only fits
+/// if replacements and the LLM left the text unchanged; otherwise the text
+/// becomes one segment spanning `duration_ms`.
+pub fn for_text(transcript: &Transcript, text: &str, duration_ms: u64) -> Transcript {
+    if text == transcript.text() {
+        return transcript.clone();
+    }
+    Transcript {
+        language: transcript.language.clone(),
+        ..Transcript::from_text(text, duration_ms)
+    }
+}
+
+/// Render the kept segments of `transcript`. Rejected transcripts render
+/// as empty documents.
+pub fn render(transcript: &Transcript, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Srt => render_srt(transcript),
+        ExportFormat::Vtt => render_vtt(transcript),
+        ExportFormat::Json => render_json(transcript),
+        ExportFormat::Markdown => render_markdown(transcript),
+    }
+}
+
+/// Parse a file written by `render`, or by another tool in the same format.
+/// Wrapped subtitle lines and cues split from one segment come back as
+/// separate segments; Markdown times are only precise to the second.
+pub fn parse(input: &str, format: ExportFormat) -> Result<Transcript, ExportError> {
+    let input = input.trim_start_matches('\u{feff}');
+    match format {
+        ExportFormat::Srt => parse_cues(input, false),
+        ExportFormat::Vtt => parse_cues(input, true),
+        ExportFormat::Json => parse_json(input),
+        ExportFormat::Markdown => parse_markdown(input),
+    }
+}
+
+// --- Subtitles ---
+
+struct Cue {
+    start_ms: u64,
+    end_ms: u64,
+    lines: Vec<String>,
+}
+
+/// Wrap each kept segment and split it into cues of at most
+/// `MAX_CUE_LINES`, sharing the segment's time by line width.
+fn cues(transcript: &Transcript) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    for segment in transcript.kept_segments() {
+        let lines = wrap(&segment.text, MAX_LINE_WIDTH);
+        let total = lines.iter().map(|l| width(l)).sum::<usize>().max(1) as u64;
+        let span = segment.end_ms.saturating_sub(segment.start_ms);
+        let mut done = 0;
+        for group in lines.chunks(MAX_CUE_LINES) {
+            let start_ms = segment.start_ms + span * done / total;
+            done += group.iter().map(|l| width(l)).sum::<usize>() as u64;
+            cues.push(Cue {
+                start_ms,
+                end_ms: segment.start_ms + span * done / total,
+                lines: group.to_vec(),
+            });
+        }
+    }
+    cues
+}
+
+fn render_srt(transcript: &Transcript) -> String {
+    cues(transcript)
+        .iter()
+        .enumerate()
+        .map(|(i, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(cue.start_ms, ','),
+                timestamp(cue.end_ms, ','),
+                cue.lines.join("\n")
+            )
+        })
+        .collect()
+}
+
+fn render_vtt(transcript: &Transcript) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues(transcript) {
+        let lines: Vec<String> = cue.lines.iter().map(|l| escape_vtt(l)).collect();
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(cue.start_ms, '.'),
+            timestamp(cue.end_ms, '.'),
+            lines.join("\n")
+        ));
+    }
+    out
+}
+
+/// Parse SRT or WebVTT cues: blank-line separated blocks of an optional
+/// identifier, a timing line and the text. WebVTT cue settings, tags and
+/// NOTE, STYLE and REGION blocks are ignored.
+fn parse_cues(input: &str, vtt: bool) -> Result<Transcript, ExportError> {
+    let mut segments = Vec::new();
+    let mut lines = input.lines().enumerate().peekable();
+    if vtt {
+        match lines.next() {
+            Some((_, header)) if header.starts_with("WEBVTT") => {}
+            _ => return Err(parse_error(0, "missing WEBVTT header")),
+        }
+        // Header metadata such as "Kind: captions" runs to the first blank line
+        while lines.next_if(|(_, l)| !l.trim().is_empty()).is_some() {}
+    }
+    loop {
+        while lines.next_if(|(_, l)| l.trim().is_empty()).is_some() {}
+        let block: Vec<(usize, &str)> = std::iter::from_fn(|| lines.next_if(|(_, l)| !l.trim().is_empty())).collect();
+        let Some(&(first, head)) = block.first() else {
+            break;
+        };
+        if vtt && (head == "NOTE" || ["NOTE ", "STYLE", "REGION"].iter().any(|p| head.starts_with(p))) {
+            continue;
+        }
+        let timing = block
+            .iter()
+            .position(|(_, l)| l.contains("-->"))
+            .filter(|&i| i <= 1)
+            .ok_or_else(|| parse_error(first, "expected a cue timing line"))?;
+        let (line, timing_line) = block[timing];
+        let (start_ms, end_ms) = parse_timing(timing_line).ok_or_else(|| parse_error(line, "invalid cue timing"))?;
+        let text_lines: Vec<String> = block[timing + 1..]
+            .iter()
+            .map(|(_, l)| if vtt { unescape_vtt(&strip_tags(l)) } else { l.trim().to_string() })
+            .collect();
+        segments.push(parsed_segment(start_ms, end_ms, &join_lines(&text_lines)));
+    }
+    Ok(Transcript {
+        segments,
+        ..Transcript::default()
+    })
+}
+
+/// `start --> end`, ignoring WebVTT cue settings after the end time.
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn unescape_vtt(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&amp;", "&")
+}
+
+/// Drop WebVTT markup such as `<v Speaker>` or `<00:00:01.000>`.
+fn strip_tags(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.trim().chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+// --- JSON ---
+
+/// The exported JSON document. Fields are only ever added, with defaults,
+/// within one `version`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonTranscript {
+    version: u32,
+    /// Detected language code, if any.
+    #[serde(default)]
+    language: Option<String>,
+    /// Concatenated segment text, trimmed.
+    #[serde(default)]
+    text: String,
+    segments: Vec<JsonSegment>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonSegment {
+    start_ms: u64,
+    end_ms: u64,
+    /// As transcribed, including Whisper's leading space.
+    text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    words: Vec<JsonWord>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonWord {
+    text: String,
+    start_ms: u64,
+    end_ms: u64,
+    prob: f32,
+}
+
+fn render_json(transcript: &Transcript) -> String {
+    let document = JsonTranscript {
+        version: JSON_VERSION,
+        language: transcript.language.as_ref().map(|l| l.code.clone()),
+        text: transcript.text(),
+        segments: transcript
+            .kept_segments()
+            .filter(|s| !s.text.trim().is_empty())
+            .map(|s| JsonSegment {
+                start_ms: s.start_ms,
+                end_ms: s.end_ms,
+                text: s.text.clone(),
+                words: s
+                    .words
+                    .iter()
+                    .map(|w| JsonWord {
+                        text: w.text.clone(),
+                        start_ms: w.start_ms,
+                        end_ms: w.end_ms,
+                        prob: w.prob,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+    // Plain strings and numbers cannot fail to serialize
+    format!("{}\n", serde_json::to_string_pretty(&document).unwrap_or_default())
+}
+
+fn parse_json(input: &str) -> Result<Transcript, ExportError> {
+    let document: JsonTranscript = serde_json::from_str(input)?;
+    if document.version > JSON_VERSION {
+        return Err(ExportError::Version(document.version));
+    }
+    Ok(Transcript {
+        segments: document
+            .segments
+            .into_iter()
+            .map(|s| Segment {
+                text: s.text,
+                words: s
+                    .words
+                    .into_iter()
+                    .map(|w| Word {
+                        text: w.text,
+                        start_ms: w.start_ms,
+                        end_ms: w.end_ms,
+                        prob: w.prob,
+                    })
+                    .collect(),
+                ..parsed_segment(s.start_ms, s.end_ms, "")
+            })
+            .collect(),
+        rejection: None,
+        language: document.language.map(language),
+    })
+}
+
+// --- Markdown ---
+
+const MARKDOWN_TITLE: &str = "# Transcript";
+const MARKDOWN_LANGUAGE: &str = "Language: ";
+
+fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = format!("{MARKDOWN_TITLE}\n\n");
+    if let Some(language) = &transcript.language {
+        out.push_str(&format!("{MARKDOWN_LANGUAGE}{}\n\n", language.code));
+    }
+    for segment in transcript.kept_segments() {
+        let text = segment.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "**[{} - {}]** {}\n\n",
+            clock(segment.start_ms),
+            clock(segment.end_ms),
+            escape_markdown(&text)
+        ));
+    }
+    out
+}
+
+/// Parse the `**[start - end]** text` paragraphs; other lines are ignored.
+fn parse_markdown(input: &str) -> Result<Transcript, ExportError> {
+    let mut transcript = Transcript::default();
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if let Some(code) = line.strip_prefix(MARKDOWN_LANGUAGE) {
+            transcript.language = Some(language(code.trim().to_string()));
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("**[") else {
+            continue;
+        };
+        let (start_ms, end_ms, text) = rest
+            .split_once("]**")
+            .and_then(|(range, text)| {
+                let (start, end) = range.split_once(" - ")?;
+                Some((parse_timestamp(start)?, parse_timestamp(end)?, text))
+            })
+            .ok_or_else(|| parse_error(index, "invalid segment timestamp"))?;
+        transcript.segments.push(parsed_segment(start_ms, end_ms, &unescape_markdown(text.trim())));
+    }
+    Ok(transcript)
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn unescape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && next.is_ascii_punctuation() => {
+                out.push(next);
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+// --- Shared ---
+
+/// `HH:MM:SS`
+fn clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// `HH:MM:SS` and milliseconds after `separator`: `,` for SRT, `.` for WebVTT.
+fn timestamp(ms: u64, separator: char) -> String {
+    format!("{}{separator}{:03}", clock(ms), ms % 1000)
+}
+
+/// `[HH:]MM:SS[.mmm]`, with `,` also accepted before the milliseconds.
+fn parse_timestamp(s: &str) -> Option<u64> {
+    let (clock, millis) = match s.split_once(['.', ',']) {
+        Some((clock, millis)) if millis.len() == 3 => (clock, millis.parse::<u64>().ok()?),
+        Some(_) => return None,
+        None => (s, 0),
+    };
+    let mut secs = 0;
+    let mut parts = 0;
+    for part in clock.split(':') {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+        parts += 1;
+    }
+    (2..=3).contains(&parts).then_some(secs * 1000 + millis)
+}
+
+fn parse_error(index: usize, message: &str) -> ExportError {
+    ExportError::Parse {
+        line: index + 1,
+        message: message.to_string(),
+    }
+}
+
+/// Language read from a file, which doesn't record detection confidence.
+fn language(code: String) -> DetectedLanguage {
+    DetectedLanguage { code, probability: 1.0 }
+}
+
+/// Segment read back from a file. Text gets the leading space Whisper puts
+/// before words so `Transcript::text` joins segments as it would have.
+fn parsed_segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+    let text = match text.chars().next() {
+        Some(c) if !is_wide(c) => format!(" {text}"),
+        _ => text.to_string(),
+    };
+    Segment {
+        start_ms,
+        end_ms,
+        text,
+        avg_prob: 1.0,
+        no_speech_prob: 0.0,
+        skipped: false,
+        words: Vec::new(),
+    }
+}
+
+// --- Wrapping ---
+
+/// Characters that render double width: CJK scripts, their punctuation and
+/// fullwidth forms.
+fn is_wide(c: char) -> bool {
+    is_cjk(c) || matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF01}'..='\u{FF60}' | '\u{FFE0}'..='\u{FFE6}')
+}
+
+fn width(text: &str) -> usize {
+    text.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum()
+}
+
+/// Must not start a line.
+fn is_closing(c: char) -> bool {
+    matches!(
+        c,
+        '。' | '，' | '、' | '．' | '！' | '？' | '：' | '；' | '」' | '』' | '）' | '〉' | '》' | '】' | '〕' | 'ー' | '…'
+    )
+}
+
+/// Must not end a line.
+fn is_opening(c: char) -> bool {
+    matches!(c, '「' | '『' | '（' | '〈' | '《' | '【' | '〔')
+}
+
+/// Break `text` into lines of at most `max` columns. Lines break at spaces
+/// or between two wide characters; a word longer than `max` gets a line of
+/// its own. Whitespace runs collapse to one space.
+fn wrap(text: &str, max: usize) -> Vec<String> {
+    // Unbreakable pieces, and whether a space precedes each
+    let mut pieces: Vec<(String, bool)> = Vec::new();
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+        let breaks = match pieces.last().and_then(|(p, _)| p.chars().last()) {
+            None => true,
+            Some(_) if space => true,
+            Some(prev) => is_wide(prev) && is_wide(c) && !is_closing(c) && !is_opening(prev),
+        };
+        if breaks {
+            pieces.push((String::new(), space && !pieces.is_empty()));
+        }
+        if let Some((piece, _)) = pieces.last_mut() {
+            piece.push(c);
+        }
+        space = false;
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for (piece, spaced) in pieces {
+        let gap = usize::from(spaced && !line.is_empty());
+        if !line.is_empty() && width(&line) + gap + width(&piece) > max {
+            lines.push(std::mem::take(&mut line));
+        } else if gap == 1 {
+            line.push(' ');
+        }
+        line.push_str(&piece);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Undo `wrap`: lines join with a space unless both sides of the break are
+/// wide characters.
+fn join_lines(lines: &[String]) -> String {
+    let mut out = String::new();
+    for line in lines.iter().filter(|l| !l.is_empty()) {
+        let joined = matches!(
+            (out.chars().last(), line.chars().next()),
+            (Some(a), Some(b)) if is_wide(a) && is_wide(b)
+        );
+        if !out.is_empty() && !joined {
+            out.push(' ');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> Segment {
+        parsed_segment(start_ms, end_ms, text)
+    }
+
+    fn sample() -> Transcript {
+        let mut skipped = segment("[BLANK_AUDIO]", 2_000, 2_500);
+        skipped.skipped = true;
+        let mut first = segment("Hello <world> & \"friends\", 1 * 2 = [two]_", 0, 1_900);
+        first.words = vec![Word {
+            text: "Hello".to_string(),
+            start_ms: 0,
+            end_ms: 400,
+            prob: 0.75,
+        }];
+        Transcript {
+            segments: vec![
+                first,
+                skipped,
+                segment("我們今天討論「語音辨識」。", 3_000, 6_500),
+                segment("Rust 很好用。", 3_725_042, 3_727_000),
+            ],
+            rejection: None,
+            language: Some(language("zh".to_string())),
+        }
+    }
+
+    fn timed_texts(transcript: &Transcript) -> Vec<(u64, u64, String)> {
+        transcript
+            .kept_segments()
+            .map(|s| (s.start_ms, s.end_ms, s.text.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_srt_round_trip() {
+        let original = sample();
+        let srt = render(&original, ExportFormat::Srt);
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:01,900\nHello <world> &"));
+        assert!(srt.contains("3\n01:02:05,042 --> 01:02:07,000\nRust 很好用。\n\n"));
+        assert!(!srt.contains("BLANK_AUDIO"));
+        let parsed = parse(&srt, ExportFormat::Srt).unwrap();
+        assert_eq!(timed_texts(&parsed), timed_texts(&original));
+        assert_eq!(parsed.text(), original.text());
+    }
+
+    #[test]
+    fn test_vtt_round_trip() {
+        let original = sample();
+        let vtt = render(&original, ExportFormat::Vtt);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.900\nHello &lt;world&gt; &amp;"));
+        let parsed = parse(&vtt, ExportFormat::Vtt).unwrap();
+        assert_eq!(timed_texts(&parsed), timed_texts(&original));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let original = sample();
+        let json = render(&original, ExportFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], JSON_VERSION);
+        assert_eq!(value["language"], "zh");
+        assert_eq!(value["text"], original.text());
+        assert_eq!(value["segments"].as_array().unwrap().len(), 3);
+        assert_eq!(value["segments"][0]["words"][0]["end_ms"], 400);
+
+        let parsed = parse(&json, ExportFormat::Json).unwrap();
+        assert_eq!(timed_texts(&parsed), timed_texts(&original));
+        assert_eq!(parsed.segments[0].words, original.segments[0].words);
+        assert_eq!(parsed.language.unwrap().code, "zh");
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let original = sample();
+        let markdown = render(&original, ExportFormat::Markdown);
+        assert!(markdown.starts_with("# Transcript\n\nLanguage: zh\n\n**[00:00:00 - 00:00:01]** Hello \\<world\\>"));
+        let parsed = parse(&markdown, ExportFormat::Markdown).unwrap();
+        // Markdown keeps whole seconds only
+        let floored: Vec<_> = timed_texts(&original)
+            .into_iter()
+            .map(|(start, end, text)| (start / 1000 * 1000, end / 1000 * 1000, text))
+            .collect();
+        assert_eq!(timed_texts(&parsed), floored);
+        assert_eq!(parsed.language.unwrap().code, "zh");
+    }
+
+    #[test]
+    fn test_rejected_transcript_renders_empty() {
+        let mut transcript = sample();
+        transcript.rejection = Some(crate::transcript::Rejection::LowConfidence { avg_prob: 0.1 });
+        assert_eq!(render(&transcript, ExportFormat::Srt), "");
+        assert_eq!(render(&transcript, ExportFormat::Vtt), "WEBVTT\n\n");
+        assert!(parse(&render(&transcript, ExportFormat::Json), ExportFormat::Json).unwrap().segments.is_empty());
+    }
+
+    #[test]
+    fn test_wrap_cjk() {
+        let text = "今天我們要討論的是語音辨識模型在長時間錄音中的表現，以及如何把逐字稿匯出成字幕檔案。";
+        let lines = wrap(text, MAX_LINE_WIDTH);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| width(l) <= MAX_LINE_WIDTH), "{lines:?}");
+        // Punctuation stays with the character before it
+        assert!(lines.iter().all(|l| !l.starts_with(is_closing)));
+        assert_eq!(join_lines(&lines), text);
+
+        assert_eq!(wrap("「你好」，世界", 4), vec!["「你", "好」，", "世界"]);
+        assert_eq!(wrap("the quick  brown fox", 10), vec!["the quick", "brown fox"]);
+        assert_eq!(wrap("supercalifragilistic is long", 10), vec!["supercalifragilistic", "is long"]);
+        // Latin glued to CJK without a space doesn't break
+        assert_eq!(wrap("用Rust寫", 4), vec!["用Rust寫"]);
+        assert_eq!(join_lines(&wrap("hello 世界 and you", 8)), "hello 世界 and you");
+    }
+
+    #[test]
+    fn test_long_segment_splits_into_cues() {
+        let text = "今天我們要討論的是語音辨識模型在長時間錄音中的表現，以及如何把逐字稿匯出成字幕檔案，最後再看看翻譯功能。";
+        let transcript = Transcript {
+            segments: vec![segment(text, 10_000, 20_000)],
+            ..Transcript::default()
+        };
+        let cues = cues(&transcript);
+        assert!(cues.len() > 1);
+        assert!(cues.iter().all(|c| c.lines.len() <= MAX_CUE_LINES));
+        assert_eq!(cues[0].start_ms, 10_000);
+        assert_eq!(cues.last().unwrap().end_ms, 20_000);
+        assert!(cues.windows(2).all(|w| w[0].end_ms == w[1].start_ms && w[0].start_ms < w[0].end_ms));
+
+        let parsed = parse(&render(&transcript, ExportFormat::Srt), ExportFormat::Srt).unwrap();
+        assert_eq!(parsed.segments.len(), cues.len());
+        assert_eq!(parsed.text(), text);
+    }
+
+    #[test]
+    fn test_parse_foreign_vtt() {
+        let vtt = "\u{feff}WEBVTT - exported\r\nKind: captions\r\n\r\nNOTE made elsewhere\r\nspanning lines\r\n\r\nSTYLE\r\n::cue { color: red }\r\n\r\nintro\r\n00:01.500 --> 00:03.000 align:start position:10%\r\n<v Alice>Hi &amp; welcome</v>\r\n\r\n00:00:04.000 --> 00:00:05.250\r\nSecond\r\nline\r\n";
+        let parsed = parse(vtt, ExportFormat::Vtt).unwrap();
+        assert_eq!(
+            timed_texts(&parsed),
+            vec![(1_500, 3_000, " Hi & welcome".to_string()), (4_000, 5_250, " Second line".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("1\n00:00:01,000 -> 00:00:02,000\nHi\n", ExportFormat::Srt).unwrap_err();
+        assert!(matches!(err, ExportError::Parse { line: 1, .. }), "{err}");
+        let err = parse("1\n00:00:01,000 --> 00:00:0x,000\nHi\n", ExportFormat::Srt).unwrap_err();
+        assert!(matches!(err, ExportError::Parse { line: 2, .. }), "{err}");
+        assert!(matches!(parse("00:00:01.000 --> 00:00:02.000\nHi", ExportFormat::Vtt), Err(ExportError::Parse { line: 1, .. })));
+        assert!(matches!(parse(r#"{"version": 2, "segments": []}"#, ExportFormat::Json), Err(ExportError::Version(2))));
+        assert!(matches!(parse("{", ExportFormat::Json), Err(ExportError::Json(_))));
+    }
+
+    #[test]
+    fn test_for_text() {
+        let transcript = sample();
+        assert_eq!(for_text(&transcript, &transcript.text(), 9_000), transcript);
+        let rewritten = for_text(&transcript, "Rewritten by the LLM.", 9_000);
+        assert_eq!(timed_texts(&rewritten), vec![(0, 9_000, "Rewritten by the LLM.".to_string())]);
+        assert_eq!(rewritten.language, transcript.language);
+    }
+
+    #[test]
+    fn test_format_extension() {
+        for format in ExportFormat::ALL {
+            assert_eq!(ExportFormat::from_extension(format.extension()), Some(format));
+        }
+        assert_eq!(ExportFormat::from_extension("VTT"), Some(ExportFormat::Vtt));
+        assert_eq!(ExportFormat::from_extension("txt"), None);
+    }
+}
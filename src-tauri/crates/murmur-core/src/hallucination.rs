@@ -0,0 +1,276 @@
+// Rejects text Whisper invents on silence or noise ("Thanks for watching",
+// subtitle credits). Built-in rules per language, adjusted by the user's
+// additions and removals in settings.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How a rule's pattern is matched. Text is lowercased and stripped of
+/// surrounding whitespace and `.!,?` first; patterns are lowercased too,
+/// except regexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// The whole text equals the pattern.
+    Exact,
+    /// The text contains the pattern.
+    Contains,
+    /// The regular expression matches somewhere in the text.
+    Regex,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterRule {
+    pub kind: RuleKind,
+    pub pattern: String,
+    /// Whisper code of the language the rule applies to; `None` for all.
+    /// Applies to every language when the spoken one isn't known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl FilterRule {
+    fn new(kind: RuleKind, pattern: &str, language: Option<&str>) -> Self {
+        Self {
+            kind,
+            pattern: pattern.to_string(),
+            language: language.map(str::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            RuleKind::Exact => "exact",
+            RuleKind::Contains => "contains",
+            RuleKind::Regex => "regex",
+        };
+        write!(f, "{kind} {:?}", self.pattern)?;
+        if let Some(language) = &self.language {
+            write!(f, " ({language})")?;
+        }
+        Ok(())
+    }
+}
+
+/// User changes to the built-in rules, stored in settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterSettings {
+    pub added: Vec<FilterRule>,
+    /// Patterns of built-in rules to drop, e.g. "bye" for people who say it.
+    pub removed: Vec<String>,
+}
+
+impl FilterSettings {
+    /// Check the added regex rules compile.
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.added {
+            if rule.kind == RuleKind::Regex {
+                Regex::new(&rule.pattern)
+                    .map_err(|e| format!("invalid hallucination filter regex {:?}: {e}", rule.pattern))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text with no letters or digits left, e.g. "..." or "♪".
+const NO_WORDS_PATTERN: &str = r"^[^\p{L}\p{N}]*$";
+
+/// Built-in rules as (kind, pattern, language).
+const BUILTIN_RULES: &[(RuleKind, &str, Option<&str>)] = &[
+    (RuleKind::Regex, NO_WORDS_PATTERN, None),
+    (RuleKind::Contains, "amara.org", None),
+    // English outros and credits from the subtitled videos Whisper trained on
+    (RuleKind::Contains, "thank you for watching", Some("en")),
+    (RuleKind::Contains, "thanks for watching", Some("en")),
+    (RuleKind::Contains, "please subscribe", Some("en")),
+    (RuleKind::Contains, "like and subscribe", Some("en")),
+    (RuleKind::Contains, "see you next time", Some("en")),
+    (RuleKind::Contains, "see you in the next", Some("en")),
+    (RuleKind::Contains, "thank you for listening", Some("en")),
+    (RuleKind::Contains, "thanks for listening", Some("en")),
+    (RuleKind::Contains, "subtitles by", Some("en")),
+    (RuleKind::Contains, "translated by", Some("en")),
+    // Short phrases produced on near-silent or very short audio
+    (RuleKind::Exact, "thank you", Some("en")),
+    (RuleKind::Exact, "thanks", Some("en")),
+    (RuleKind::Exact, "you", Some("en")),
+    (RuleKind::Exact, "bye", Some("en")),
+    (RuleKind::Exact, "oh", Some("en")),
+    (RuleKind::Exact, "ah", Some("en")),
+    (RuleKind::Exact, "hmm", Some("en")),
+    (RuleKind::Contains, "謝謝觀看", Some("zh")),
+    (RuleKind::Contains, "感謝觀看", Some("zh")),
+    (RuleKind::Contains, "感謝收看", Some("zh")),
+    (RuleKind::Contains, "請訂閱", Some("zh")),
+    (RuleKind::Contains, "字幕", Some("zh")),
+    (RuleKind::Contains, "谢谢观看", Some("zh")),
+    (RuleKind::Contains, "感谢观看", Some("zh")),
+    (RuleKind::Contains, "请订阅", Some("zh")),
+    (RuleKind::Exact, "謝謝", Some("zh")),
+    (RuleKind::Exact, "谢谢", Some("zh")),
+    (RuleKind::Exact, "嗯", Some("zh")),
+    (RuleKind::Contains, "ご視聴ありがとうございました", Some("ja")),
+];
+
+/// The built-in rules, for showing in settings.
+pub fn builtin_rules() -> Vec<FilterRule> {
+    BUILTIN_RULES
+        .iter()
+        .map(|(kind, pattern, language)| FilterRule::new(*kind, pattern, *language))
+        .collect()
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: FilterRule,
+    regex: Option<Regex>,
+}
+
+/// Compiled rule set. Build once and reuse; regex rules are compiled here.
+#[derive(Debug)]
+pub struct HallucinationFilter {
+    rules: Vec<CompiledRule>,
+}
+
+impl HallucinationFilter {
+    /// Built-in rules minus `settings.removed`, plus `settings.added`.
+    /// Regex rules that don't compile are skipped with a warning.
+    pub fn new(settings: &FilterSettings) -> Self {
+        let removed = |rule: &FilterRule| {
+            settings
+                .removed
+                .iter()
+                .any(|pattern| pattern.to_lowercase() == rule.pattern.to_lowercase())
+        };
+        let rules = builtin_rules()
+            .into_iter()
+            .filter(|rule| !removed(rule))
+            .chain(settings.added.iter().cloned())
+            .filter_map(|rule| {
+                let regex = match rule.kind {
+                    RuleKind::Regex => match Regex::new(&rule.pattern) {
+                        Ok(regex) => Some(regex),
+                        Err(e) => {
+                            log::warn!("skipping hallucination filter rule {rule}: {e}");
+                            return None;
+                        }
+                    },
+                    _ => None,
+                };
+                Some(CompiledRule { rule, regex })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Filter with only the built-in rules.
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<HallucinationFilter> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::new(&FilterSettings::default()))
+    }
+
+    /// The first rule rejecting `text` as a hallucination, if any.
+    /// `language` is the Whisper code of the speech when known.
+    pub fn check(&self, text: &str, language: Option<&str>) -> Option<&FilterRule> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        let lower = text.to_lowercase();
+        let normalized = lower
+            .trim_matches(|c: char| c.is_whitespace() || matches!(c, '.' | '!' | ',' | '?'));
+        self.rules
+            .iter()
+            .filter(|c| match (&c.rule.language, language) {
+                (Some(rule_language), Some(language)) => rule_language == language,
+                _ => true,
+            })
+            .find(|c| match c.rule.kind {
+                RuleKind::Exact => normalized == c.rule.pattern.to_lowercase(),
+                RuleKind::Contains => normalized.contains(&c.rule.pattern.to_lowercase()),
+                RuleKind::Regex => c.regex.as_ref().is_some_and(|r| r.is_match(normalized)),
+            })
+            .map(|c| &c.rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(text: &str) -> bool {
+        HallucinationFilter::builtin().check(text, None).is_some()
+    }
+
+    #[test]
+    fn test_builtin_rules() {
+        assert!(rejects("Thank you for watching."));
+        assert!(rejects("謝謝觀看"));
+        assert!(rejects("  thanks for watching!  "));
+        assert!(rejects("Subtitles by Amara.org"));
+        assert!(rejects("...")); // only punctuation after trim
+        assert!(rejects("♪"));
+        // Short exact-match hallucinations
+        assert!(rejects("Thank you."));
+        assert!(rejects("Thanks!"));
+        assert!(rejects("  You  "));
+        assert!(rejects("謝謝"));
+        assert!(rejects("Bye."));
+        // Valid short text should pass through
+        assert!(!rejects("好")); // valid CJK single char
+        assert!(!rejects("OK"));
+        assert!(!rejects("Okay."));
+        assert!(!rejects("Goodbye, see you tomorrow"));
+        assert!(!rejects("Open https://example.com and www.example.org"));
+        assert!(!rejects("Hello, how are you today?"));
+        assert!(!rejects("The meeting is at 3pm"));
+        assert!(!rejects("Thank you for your help")); // contains "thank you" but longer
+        assert!(!rejects("")); // empty is not hallucination (handled elsewhere)
+    }
+
+    #[test]
+    fn test_rules_apply_per_language() {
+        let filter = HallucinationFilter::builtin();
+        let rule = filter.check("Thank you.", Some("en")).unwrap();
+        assert_eq!(rule.to_string(), r#"exact "thank you" (en)"#);
+        assert!(filter.check("Thank you.", Some("zh")).is_none());
+        assert!(filter.check("謝謝", Some("ja")).is_none());
+        // Language-independent rules apply everywhere
+        assert!(filter.check("...", Some("ja")).is_some());
+        assert!(filter.check("amara.org", Some("de")).is_some());
+    }
+
+    #[test]
+    fn test_user_additions_and_removals() {
+        let settings = FilterSettings {
+            added: vec![
+                FilterRule::new(RuleKind::Exact, "Merci", Some("fr")),
+                FilterRule::new(RuleKind::Regex, r"^(la )+la$", None),
+                FilterRule::new(RuleKind::Regex, "(unclosed", None),
+            ],
+            removed: vec!["Bye".to_string()],
+        };
+        assert!(settings.validate().is_err());
+        let filter = HallucinationFilter::new(&settings);
+        assert!(filter.check("Bye.", Some("en")).is_none());
+        assert_eq!(filter.check("merci !", Some("fr")).unwrap().pattern, "Merci");
+        assert_eq!(filter.check("La la la", None).unwrap().kind, RuleKind::Regex);
+        assert!(filter.check("Thanks!", Some("en")).is_some());
+    }
+
+    #[test]
+    fn test_filter_settings_json() {
+        let json = r#"{"added": [{"kind": "contains", "pattern": "sponsored by"}], "removed": ["you"]}"#;
+        let settings: FilterSettings = serde_json::from_str(json).unwrap();
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.added[0].language, None);
+        let filter = HallucinationFilter::new(&settings);
+        assert!(filter.check("This video is sponsored by", None).is_some());
+        assert!(filter.check("you", None).is_none());
+    }
+}
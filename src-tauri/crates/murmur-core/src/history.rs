@@ -0,0 +1,383 @@
+// Local transcription history: one SQLite row per result with its mode,
+// engine, foreground app, raw Whisper text and final text. Search runs on
+// an FTS5 trigram index, which matches substrings and so works for CJK
+// text that has no spaces for a word tokenizer to split on.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use thiserror::Error;
+
+use crate::state::RecordingMode;
+
+/// File name of the history database within the history directory.
+pub const HISTORY_FILE: &str = "history.db";
+
+/// Bumped when the schema changes; stored in `PRAGMA user_version`.
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS entries (
+    id INTEGER PRIMARY KEY,
+    created_at INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    engine TEXT NOT NULL,
+    app TEXT,
+    raw_text TEXT NOT NULL,
+    text TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    language TEXT
+);
+CREATE INDEX IF NOT EXISTS entries_created_at ON entries (created_at);
+CREATE VIRTUAL TABLE IF NOT EXISTS entries_fts USING fts5(
+    raw_text, text, content = 'entries', content_rowid = 'id', tokenize = 'trigram'
+);
+CREATE TRIGGER IF NOT EXISTS entries_insert AFTER INSERT ON entries BEGIN
+    INSERT INTO entries_fts (rowid, raw_text, text) VALUES (new.id, new.raw_text, new.text);
+END;
+CREATE TRIGGER IF NOT EXISTS entries_delete AFTER DELETE ON entries BEGIN
+    INSERT INTO entries_fts (entries_fts, rowid, raw_text, text)
+    VALUES ('delete', old.id, old.raw_text, old.text);
+END;
+";
+
+const COLUMNS: &str = "e.id, e.created_at, e.mode, e.engine, e.app, e.raw_text, e.text, e.duration_ms, e.language";
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("history database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("failed to create history directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("history database {0} was created by a newer version")]
+    NewerSchema(PathBuf),
+}
+
+/// One recorded result.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HistoryEntry {
+    /// Assigned by `History::add`; ignored when adding.
+    pub id: i64,
+    /// Unix time in milliseconds.
+    pub created_at: i64,
+    pub mode: RecordingMode,
+    /// Speech engine name, e.g. "local" or "groq".
+    pub engine: String,
+    /// Foreground app when the recording stopped, if known.
+    pub app: Option<String>,
+    /// Whisper output before replacements and LLM processing.
+    pub raw_text: String,
+    /// Final text, as pasted.
+    pub text: String,
+    pub duration_ms: u64,
+    pub language: Option<String>,
+}
+
+/// The history database.
+pub struct History {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl History {
+    /// Open or create the database at `path`, creating its directory.
+    pub fn open(path: &Path) -> Result<Self, HistoryError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let history = Self {
+            conn: Connection::open(path)?,
+            path: path.to_path_buf(),
+        };
+        history.migrate()?;
+        Ok(history)
+    }
+
+    #[cfg(test)]
+    fn open_in_memory() -> Self {
+        let history = Self {
+            conn: Connection::open_in_memory().unwrap(),
+            path: PathBuf::new(),
+        };
+        history.migrate().unwrap();
+        history
+    }
+
+    fn migrate(&self) -> Result<(), HistoryError> {
+        let version: i32 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(HistoryError::NewerSchema(self.path.clone()));
+        }
+        self.conn.execute_batch(SCHEMA)?;
+        self.conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Move the database to `path`. If a database already exists there it
+    /// is opened instead, and this one is left in place untouched. On error
+    /// this one stays open where it was.
+    pub fn relocate(&mut self, path: &Path) -> Result<(), HistoryError> {
+        if path == self.path {
+            return Ok(());
+        }
+        if path.exists() {
+            *self = Self::open(path)?;
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Writes a compacted copy; works across file systems
+        let copied = self
+            .conn
+            .execute("VACUUM INTO ?1", [path.to_string_lossy()])
+            .map_err(HistoryError::from)
+            .and_then(|_| Self::open(path));
+        let moved = match copied {
+            Ok(moved) => moved,
+            Err(e) => {
+                // Don't leave a partial copy to be opened next time
+                let _ = std::fs::remove_file(path);
+                return Err(e);
+            }
+        };
+        let old = std::mem::replace(self, moved);
+        let old_path = old.path.clone();
+        // Closed before removal, which Windows requires
+        drop(old);
+        if let Err(e) = std::fs::remove_file(&old_path) {
+            log::warn!("failed to remove old history database {}: {e}", old_path.display());
+        }
+        Ok(())
+    }
+
+    /// Record `entry`, returning its id.
+    pub fn add(&self, entry: &HistoryEntry) -> Result<i64, HistoryError> {
+        self.conn.execute(
+            "INSERT INTO entries (created_at, mode, engine, app, raw_text, text, duration_ms, language)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.created_at,
+                entry.mode.event_mode_str(),
+                entry.engine,
+                entry.app,
+                entry.raw_text,
+                entry.text,
+                entry.duration_ms as i64,
+                entry.language,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<HistoryEntry>, HistoryError> {
+        let sql = format!("SELECT {COLUMNS} FROM entries e WHERE e.id = ?1");
+        Ok(self.conn.query_row(&sql, [id], entry_from_row).optional()?)
+    }
+
+    /// Entries newest first, skipping the first `offset`.
+    pub fn list(&self, offset: usize, limit: usize) -> Result<Vec<HistoryEntry>, HistoryError> {
+        let sql = format!("SELECT {COLUMNS} FROM entries e ORDER BY e.created_at DESC, e.id DESC LIMIT ?1 OFFSET ?2");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt.query_map([limit as i64, offset as i64], entry_from_row)?;
+        Ok(entries.collect::<Result<_, _>>()?)
+    }
+
+    /// Entries whose raw or final text contains every whitespace-separated
+    /// term of `query`, ignoring ASCII case; newest first.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<HistoryEntry>, HistoryError> {
+        let patterns: Vec<String> = query.split_whitespace().map(like_pattern).collect();
+        if patterns.is_empty() {
+            return self.list(0, limit);
+        }
+        let conditions: Vec<String> = (1..=patterns.len())
+            .map(|i| format!("(f.raw_text LIKE ?{i} ESCAPE '\\' OR f.text LIKE ?{i} ESCAPE '\\')"))
+            .collect();
+        let sql = format!(
+            "SELECT {COLUMNS} FROM entries e JOIN entries_fts f ON f.rowid = e.id
+             WHERE {} ORDER BY e.created_at DESC, e.id DESC LIMIT {limit}",
+            conditions.join(" AND ")
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt.query_map(params_from_iter(&patterns), entry_from_row)?;
+        Ok(entries.collect::<Result<_, _>>()?)
+    }
+
+    /// Returns false if there was no such entry.
+    pub fn delete(&self, id: i64) -> Result<bool, HistoryError> {
+        Ok(self.conn.execute("DELETE FROM entries WHERE id = ?1", [id])? > 0)
+    }
+
+    /// Delete entries older than `max_age` before `now_ms`, returning how
+    /// many were removed.
+    pub fn prune(&self, max_age: Duration, now_ms: i64) -> Result<usize, HistoryError> {
+        let cutoff = now_ms.saturating_sub(max_age.as_millis() as i64);
+        let removed = self.conn.execute("DELETE FROM entries WHERE created_at < ?1", [cutoff])?;
+        if removed > 0 {
+            log::info!("pruned {removed} history entries");
+        }
+        Ok(removed)
+    }
+
+    pub fn count(&self) -> Result<usize, HistoryError> {
+        let count: i64 = self.conn.query_row("SELECT count(*) FROM entries", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
+
+/// Current Unix time in milliseconds, as stored in `created_at`.
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// `%term%` with LIKE wildcards in `term` escaped.
+fn like_pattern(term: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn entry_from_row(row: &Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    let mode: String = row.get(2)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        created_at: row.get(1)?,
+        mode: RecordingMode::from_event_mode_str(&mode).unwrap_or_default(),
+        engine: row.get(3)?,
+        app: row.get(4)?,
+        raw_text: row.get(5)?,
+        text: row.get(6)?,
+        duration_ms: row.get::<_, i64>(7)? as u64,
+        language: row.get(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(created_at: i64, raw_text: &str, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            created_at,
+            mode: RecordingMode::Dictation,
+            engine: "local".to_string(),
+            app: Some("Slack".to_string()),
+            raw_text: raw_text.to_string(),
+            text: text.to_string(),
+            duration_ms: 4_200,
+            language: Some("en".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_add_get_list() {
+        let history = History::open_in_memory();
+        let first = history.add(&entry(1_000, "um hello there", "Hello there.")).unwrap();
+        let mut command = entry(2_000, "make it formal", "Dear team,");
+        command.mode = RecordingMode::VoiceCommand;
+        command.app = None;
+        command.language = None;
+        let second = history.add(&command).unwrap();
+
+        let got = history.get(first).unwrap().unwrap();
+        assert_eq!(got, HistoryEntry { id: first, ..entry(1_000, "um hello there", "Hello there.") });
+        assert_eq!(history.get(second).unwrap().unwrap(), HistoryEntry { id: second, ..command });
+        assert!(history.get(99).unwrap().is_none());
+
+        let ids: Vec<i64> = history.list(0, 10).unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![second, first]);
+        assert_eq!(history.list(1, 10).unwrap()[0].id, first);
+        assert_eq!(history.count().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_search() {
+        let history = History::open_in_memory();
+        let english = history.add(&entry(1_000, "the budget meeting", "The budget meeting is at 3.")).unwrap();
+        let chinese = history.add(&entry(2_000, "我們今天開會討論預算", "我們今天開會，討論預算。")).unwrap();
+        let percent = history.add(&entry(3_000, "growth was 100 percent", "Growth was 100%.")).unwrap();
+
+        let ids = |query: &str| -> Vec<i64> { history.search(query, 10).unwrap().iter().map(|e| e.id).collect() };
+        assert_eq!(ids("BUDGET"), vec![english]);
+        // Raw text matches too, and short CJK terms work
+        assert_eq!(ids("開會"), vec![chinese]);
+        assert_eq!(ids("預"), vec![chinese]);
+        assert_eq!(ids("budget 3."), vec![english]);
+        assert_eq!(ids("budget 預算"), Vec::<i64>::new());
+        // LIKE wildcards are literal
+        assert_eq!(ids("100%"), vec![percent]);
+        assert_eq!(ids("%"), vec![percent]);
+        assert_eq!(ids("  "), vec![percent, chinese, english]);
+    }
+
+    #[test]
+    fn test_delete_and_prune() {
+        let history = History::open_in_memory();
+        let old = history.add(&entry(1_000, "old", "Old.")).unwrap();
+        let new = history.add(&entry(90_000_000, "new", "New.")).unwrap();
+
+        assert!(history.delete(new).unwrap());
+        assert!(!history.delete(new).unwrap());
+        // Deleted entries leave the search index too
+        assert!(history.search("new", 10).unwrap().is_empty());
+
+        history.add(&entry(90_000_000, "kept", "Kept.")).unwrap();
+        let day = Duration::from_secs(86_400);
+        assert_eq!(history.prune(day, 86_400_000 + 1_000).unwrap(), 0);
+        assert_eq!(history.prune(day, 86_400_000 + 1_001).unwrap(), 1);
+        assert!(history.get(old).unwrap().is_none());
+        assert_eq!(history.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_open_and_relocate() {
+        let dir = std::env::temp_dir().join(format!("murmur-history-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("a").join(HISTORY_FILE);
+        let history = History::open(&path).unwrap();
+        let id = history.add(&entry(1_000, "hello", "Hello.")).unwrap();
+        drop(history);
+
+        let mut history = History::open(&path).unwrap();
+        assert_eq!(history.get(id).unwrap().unwrap().text, "Hello.");
+
+        // A failed move keeps the database open where it was
+        let blocker = dir.join("file");
+        std::fs::write(&blocker, "").unwrap();
+        assert!(history.relocate(&blocker.join(HISTORY_FILE)).is_err());
+        assert_eq!(history.path(), path);
+        assert_eq!(history.count().unwrap(), 1);
+
+        let moved_path = dir.join("b").join(HISTORY_FILE);
+        history.relocate(&moved_path).unwrap();
+        assert_eq!(history.path(), moved_path);
+        assert!(!path.exists());
+        assert_eq!(history.search("hello", 10).unwrap().len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_newer_schema_rejected() {
+        let dir = std::env::temp_dir().join(format!("murmur-history-schema-{}", std::process::id()));
+        let path = dir.join(HISTORY_FILE);
+        std::fs::create_dir_all(&dir).unwrap();
+        Connection::open(&path).unwrap().pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        assert!(matches!(History::open(&path), Err(HistoryError::NewerSchema(_))));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,20 @@
+// Tauri-free core of Murmur Voice: audio capture, Whisper transcription,
+// model management, settings and LLM post-processing. Shared by the desktop
+// app and headless tools.
+pub mod audio;
+pub mod buffer;
+pub mod engine;
+pub mod export;
+pub mod hallucination;
+pub mod history;
+pub mod llm;
+pub mod longform;
+pub mod model;
+pub mod pipeline;
+pub mod repetition;
+pub mod resample;
+pub mod settings;
+pub mod state;
+pub mod streaming;
+pub mod transcript;
+pub mod whisper;
@@ -0,0 +1,389 @@
+// Long-form recording for meetings and lectures. The recording is
+// transcribed while it runs, in chunks cut at pauses, so stopping only has
+// the last chunk left and transcribed audio doesn't have to stay in memory.
+// With retention on, each chunk is appended to a WAV file before it's freed.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::audio;
+use crate::buffer::AudioSnapshot;
+use crate::engine::{EngineError, SpeechEngine};
+use crate::llm;
+use crate::repetition;
+use crate::streaming::{context_prompt, locked_language};
+use crate::transcript::{DetectedLanguage, Segment, TranscribeOptions, Transcript};
+
+/// Audio waited for before cutting a chunk (15s at 16kHz); shorter chunks
+/// give Whisper too little context.
+const MIN_CHUNK_SAMPLES: usize = 240_000;
+/// Longest chunk (28s), inside Whisper's 30s window. Speech running this
+/// long without a pause is cut mid-phrase.
+const MAX_CHUNK_SAMPLES: usize = 448_000;
+/// Silence at the end of the audio so far must last this long (0.5s) to
+/// count as a pause; anything shorter may be a gap between words.
+const MIN_PAUSE_SAMPLES: usize = 8_000;
+
+/// Transcription state for one long-form recording. Call `update` with the
+/// growing recording, then `finish` with the complete one.
+#[derive(Default)]
+pub struct LongFormTranscriber {
+    /// Transcribed segments, timed from the start of the recording.
+    segments: Vec<Segment>,
+    /// Samples transcribed so far; the next chunk starts here.
+    transcribed: usize,
+    language: Option<DetectedLanguage>,
+    audio_file: Option<AudioFile>,
+}
+
+/// WAV file that transcribed chunks are appended to.
+struct AudioFile {
+    path: PathBuf,
+    writer: hound::WavWriter<BufWriter<File>>,
+}
+
+impl LongFormTranscriber {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the recording in a WAV file at `path`, written as it's
+    /// transcribed, instead of discarding it.
+    pub fn retain_audio(&mut self, path: &Path) -> Result<(), hound::Error> {
+        let writer = hound::WavWriter::create(path, llm::WAV_SPEC)?;
+        self.audio_file = Some(AudioFile {
+            path: path.to_path_buf(),
+            writer,
+        });
+        Ok(())
+    }
+
+    /// Drop the recording, deleting the retained audio if any.
+    pub fn discard(mut self) {
+        if let Some(file) = self.audio_file.take() {
+            drop(file.writer);
+            if let Err(e) = std::fs::remove_file(&file.path) {
+                log::warn!("failed to delete {}: {e}", file.path.display());
+            }
+        }
+    }
+
+    /// Samples at the start of the recording already transcribed, which the
+    /// recorder no longer needs to hold.
+    pub fn transcribed_samples(&self) -> usize {
+        self.transcribed
+    }
+
+    /// The text so far, for display.
+    pub fn preview(&self) -> Transcript {
+        Transcript {
+            segments: self.segments.clone(),
+            rejection: None,
+            language: self.language.clone(),
+        }
+    }
+
+    /// Transcribe every chunk of `recording`, the audio so far, that ends
+    /// at a pause. Returns true if anything was transcribed.
+    pub fn update(
+        &mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        options: &TranscribeOptions,
+    ) -> Result<bool, EngineError> {
+        let mut progressed = false;
+        while let Some(end) = self.next_cut(recording) {
+            self.transcribe_chunk(engine, recording, self.transcribed..end, options)?;
+            progressed = true;
+        }
+        Ok(progressed)
+    }
+
+    /// Transcribe the rest of `recording` and return the transcript of the
+    /// whole recording, with the path of the retained audio if any.
+    pub fn finish(
+        mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        options: &TranscribeOptions,
+    ) -> Result<(Transcript, Option<PathBuf>), EngineError> {
+        while let Some(end) = self.next_cut(recording) {
+            self.transcribe_chunk(engine, recording, self.transcribed..end, options)?;
+        }
+        if self.transcribed < recording.len() {
+            self.transcribe_chunk(engine, recording, self.transcribed..recording.len(), options)?;
+        }
+        log::info!(
+            "long-form: transcribed {}ms in {} segment(s)",
+            audio::samples_to_ms(recording.len()),
+            self.segments.len()
+        );
+
+        let path = self.audio_file.take().and_then(|file| match file.writer.finalize() {
+            Ok(()) => {
+                log::info!("long-form: audio saved to {}", file.path.display());
+                Some(file.path)
+            }
+            Err(e) => {
+                log::error!("failed to save recording to {}: {e}", file.path.display());
+                None
+            }
+        });
+        let transcript = Transcript {
+            segments: self.segments,
+            rejection: None,
+            language: self.language,
+        };
+        Ok((transcript, path))
+    }
+
+    /// End of the next chunk of `recording` after the transcribed part.
+    fn next_cut(&self, recording: &AudioSnapshot) -> Option<usize> {
+        let end = (self.transcribed + MAX_CHUNK_SAMPLES).min(recording.len());
+        let pending = recording.range(self.transcribed..end);
+        let cut = find_cut(&pending, recording.len() - self.transcribed)?;
+        Some(self.transcribed + cut)
+    }
+
+    /// Transcribe `range` of `recording`, save it if retained, and move
+    /// past it. Chunks without speech are skipped.
+    fn transcribe_chunk(
+        &mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        range: Range<usize>,
+        options: &TranscribeOptions,
+    ) -> Result<(), EngineError> {
+        let samples = recording.range(range.clone());
+        if audio::detect_speech(&samples).is_empty() {
+            log::debug!("long-form: no speech in {}ms chunk, skipping", audio::samples_to_ms(samples.len()));
+        } else {
+            let mut clip = samples.clone();
+            if clip.len() < audio::MIN_TRANSCRIBE_SAMPLES {
+                clip.to_mut().resize(audio::MIN_TRANSCRIBE_SAMPLES, 0.0);
+            }
+            let prompt = context_prompt(options.initial_prompt, &self.segments);
+            let options = TranscribeOptions {
+                language: locked_language(self.language.as_ref()).unwrap_or(options.language),
+                initial_prompt: &prompt,
+                ..*options
+            };
+            let mut transcript = engine.transcribe(&clip, &options)?;
+            if let Some(rejection) = &transcript.rejection {
+                log::info!("long-form: discarded chunk at {}ms: {rejection}", audio::samples_to_ms(range.start));
+            }
+            if let Some(detected) = transcript.language.take() {
+                if locked_language(self.language.as_ref()).is_none() {
+                    self.language = Some(detected);
+                }
+            }
+            let offset_ms = audio::samples_to_ms(range.start);
+            let end_ms = audio::samples_to_ms(range.end);
+            transcript.map_times(|ms| (ms + offset_ms).min(end_ms));
+            repetition::remove_loops(&mut transcript);
+            self.segments.extend(transcript.kept_segments().cloned());
+        }
+        self.save(&samples);
+        self.transcribed = range.end;
+        Ok(())
+    }
+
+    /// Append `samples` to the retained audio. A write error stops
+    /// retention rather than the recording.
+    fn save(&mut self, samples: &[f32]) {
+        let Some(file) = self.audio_file.as_mut() else {
+            return;
+        };
+        if let Err(e) = samples.iter().try_for_each(|&s| file.writer.write_sample(llm::pcm16(s))) {
+            log::error!("failed to write recording to {}: {e}", file.path.display());
+            self.audio_file = None;
+        }
+    }
+}
+
+/// Where to end a chunk starting at `pending[0]`, `available` samples of
+/// which have been recorded (`pending` holds at most one chunk of them).
+/// Cuts in the middle of the last pause that leaves a long enough chunk,
+/// else at the longest chunk if that much audio is waiting. `None` means
+/// wait for more audio.
+fn find_cut(pending: &[f32], available: usize) -> Option<usize> {
+    if available < MIN_CHUNK_SAMPLES {
+        return None;
+    }
+    let spans = audio::detect_speech(pending);
+    if spans.is_empty() {
+        return Some(pending.len());
+    }
+    // Silence after the last span counts as a pause too
+    let pauses = spans
+        .windows(2)
+        .map(|w| (w[0].end, w[1].start))
+        .chain(spans.last().map(|last| (last.end, pending.len())));
+    let cut = pauses
+        .filter(|(start, end)| end - start >= MIN_PAUSE_SAMPLES)
+        .map(|(start, end)| (start + end) / 2)
+        .rfind(|&mid| mid >= MIN_CHUNK_SAMPLES);
+    cut.or((available >= MAX_CHUNK_SAMPLES).then_some(MAX_CHUNK_SAMPLES))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::SampleBuffer;
+    use crate::streaming::ms_to_samples;
+    use std::sync::Mutex;
+
+    /// Engine that returns one segment per call, spanning the clip and
+    /// naming the call, and records the clips it was given.
+    #[derive(Default)]
+    struct ChunkEngine {
+        calls: Mutex<Vec<(usize, String)>>,
+    }
+
+    impl SpeechEngine for ChunkEngine {
+        fn name(&self) -> &str {
+            "chunks"
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+
+        fn supports_streaming(&self) -> bool {
+            false
+        }
+
+        fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push((samples.len(), options.initial_prompt.to_string()));
+            Ok(Transcript {
+                segments: vec![Segment {
+                    start_ms: 0,
+                    end_ms: audio::samples_to_ms(samples.len()),
+                    text: format!(" Chunk {}.", calls.len()),
+                    avg_prob: 0.9,
+                    no_speech_prob: 0.0,
+                    skipped: false,
+                    words: Vec::new(),
+                }],
+                ..Transcript::default()
+            })
+        }
+    }
+
+    /// Quiet noise with a tone wherever `speech` says, in ms.
+    fn audio_with_speech(total_ms: u64, speech: &[(u64, u64)]) -> Vec<f32> {
+        (0..ms_to_samples(total_ms))
+            .map(|i| {
+                let ms = audio::samples_to_ms(i);
+                let noise = if i % 2 == 0 { 0.002 } else { -0.002 };
+                if speech.iter().any(|&(s, e)| ms >= s && ms < e) {
+                    noise + 0.3 * (i as f32 * 0.19).sin()
+                } else {
+                    noise
+                }
+            })
+            .collect()
+    }
+
+    fn snapshot(samples: &[f32]) -> AudioSnapshot {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(samples);
+        buffer.snapshot()
+    }
+
+    #[test]
+    fn test_find_cut_waits_for_enough_audio() {
+        let samples = audio_with_speech(10_000, &[(0, 4_000), (6_000, 10_000)]);
+        assert_eq!(find_cut(&samples, samples.len()), None);
+    }
+
+    #[test]
+    fn test_find_cut_splits_at_last_pause() {
+        let samples = audio_with_speech(20_000, &[(0, 8_000), (9_000, 16_000), (18_000, 20_000)]);
+        let cut = find_cut(&samples, samples.len()).unwrap();
+        assert!((ms_to_samples(16_200)..ms_to_samples(17_800)).contains(&cut), "cut at {cut}");
+    }
+
+    #[test]
+    fn test_find_cut_in_continuous_speech() {
+        let samples = audio_with_speech(20_000, &[(0, 20_000)]);
+        assert_eq!(find_cut(&samples, samples.len()), None);
+
+        let samples = audio_with_speech(28_000, &[(0, 28_000)]);
+        assert_eq!(find_cut(&samples, samples.len() + 5_000), Some(MAX_CHUNK_SAMPLES));
+    }
+
+    #[test]
+    fn test_find_cut_takes_whole_silence() {
+        let samples = audio_with_speech(16_000, &[]);
+        assert_eq!(find_cut(&samples, samples.len()), Some(samples.len()));
+    }
+
+    #[test]
+    fn test_update_transcribes_chunks_as_they_end() {
+        let samples = audio_with_speech(
+            50_000,
+            &[(0, 8_000), (9_000, 16_000), (18_000, 30_000), (32_000, 42_000), (44_000, 50_000)],
+        );
+        let engine = ChunkEngine::default();
+        let options = TranscribeOptions::new("auto", "Murmur");
+        let mut long_form = LongFormTranscriber::new();
+
+        assert!(!long_form.update(&engine, &snapshot(&samples[..ms_to_samples(12_000)]), &options).unwrap());
+        // The pause at 30-32s would leave too short a chunk after the one at 16-18s
+        assert!(long_form.update(&engine, &snapshot(&samples[..ms_to_samples(34_000)]), &options).unwrap());
+        let first = long_form.transcribed_samples();
+        assert!((ms_to_samples(16_000)..ms_to_samples(18_000)).contains(&first), "cut at {first}");
+        assert_eq!(long_form.preview().text(), "Chunk 1.");
+
+        let (transcript, path) = long_form.finish(&engine, &snapshot(&samples), &options).unwrap();
+        assert!(path.is_none());
+        assert_eq!(transcript.text(), "Chunk 1. Chunk 2. Chunk 3.");
+        assert_eq!(transcript.segments[1].start_ms, audio::samples_to_ms(first));
+        assert!((42_000..44_000).contains(&transcript.segments[2].start_ms));
+        assert_eq!(transcript.segments[2].end_ms, 50_000);
+
+        let calls = engine.calls.lock().unwrap();
+        assert_eq!(calls.iter().map(|c| c.0).sum::<usize>(), samples.len());
+        assert_eq!(calls[2].1, "Murmur Chunk 1. Chunk 2.");
+    }
+
+    #[test]
+    fn test_silent_chunk_is_skipped() {
+        let samples = audio_with_speech(40_000, &[(20_000, 22_000)]);
+        let engine = ChunkEngine::default();
+        let long_form = LongFormTranscriber::new();
+        let (transcript, _) = long_form.finish(&engine, &snapshot(&samples), &TranscribeOptions::default()).unwrap();
+        assert_eq!(transcript.text(), "Chunk 1.");
+        assert_eq!(engine.calls.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_retained_audio_covers_recording() {
+        let samples = audio_with_speech(35_000, &[(0, 10_000), (12_000, 20_000), (30_000, 35_000)]);
+        let path = std::env::temp_dir().join(format!("murmur-longform-test-{}.wav", std::process::id()));
+        let mut long_form = LongFormTranscriber::new();
+        long_form.retain_audio(&path).unwrap();
+        let (_, saved) = long_form
+            .finish(&ChunkEngine::default(), &snapshot(&samples), &TranscribeOptions::default())
+            .unwrap();
+        assert_eq!(saved.as_deref(), Some(path.as_path()));
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len() as usize, samples.len());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_discard_deletes_retained_audio() {
+        let path = std::env::temp_dir().join(format!("murmur-longform-discard-{}.wav", std::process::id()));
+        let mut long_form = LongFormTranscriber::new();
+        long_form.retain_audio(&path).unwrap();
+        assert!(path.exists());
+        long_form.discard();
+        assert!(!path.exists());
+    }
+}
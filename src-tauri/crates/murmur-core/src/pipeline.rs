@@ -0,0 +1,812 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::audio;
+use crate::buffer::AudioSnapshot;
+use crate::engine::{self, EngineError, SpeechEngine};
+use crate::hallucination::HallucinationFilter;
+use crate::llm::{self, LlmError};
+use crate::longform::LongFormTranscriber;
+use crate::repetition;
+use crate::settings::Settings;
+use crate::state::RecordingMode;
+use crate::streaming::StreamingTranscriber;
+use crate::transcript::{DetectedLanguage, Rejection, TranscribeOptions, Transcript};
+
+/// Pipeline stages reported to a `PipelineObserver`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Transcribing,
+    Processing,
+}
+
+/// Receives progress from `Pipeline`. Every method defaults to a no-op so
+/// headless callers only implement what they care about.
+pub trait PipelineObserver {
+    fn stage(&self, _stage: Stage) {}
+    /// `language` is set when the engine auto-detected it.
+    fn engine_info(&self, _name: &str, _local: bool, _language: Option<&DetectedLanguage>) {}
+    fn enhancer_info(&self, _name: &str, _local: bool) {}
+    /// The engine discarded its transcription, e.g. as a hallucination.
+    fn rejected(&self, _rejection: &Rejection) {}
+    /// Non-fatal problem, e.g. LLM failure where the raw text is kept.
+    fn warning(&self, _message: &str) {}
+}
+
+impl PipelineObserver for () {}
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error("whisper engine is not loaded")]
+    EngineNotLoaded,
+    #[error("Enable AI Processing in Settings to use this mode")]
+    EnhancerRequired,
+    #[error("Translating speech needs the Local (Whisper) engine")]
+    TranslationNeedsLocal,
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+}
+
+impl serde::Serialize for PipelineError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// What to do with a transcription once it is available.
+#[derive(Debug, Clone)]
+pub struct PipelineRequest {
+    pub mode: RecordingMode,
+    /// Selected text or clipboard content for VoiceCommand/ClipboardRewrite.
+    pub context: String,
+    /// LLM tone style, e.g. "formal" or "technical".
+    pub style: String,
+    /// Whisper code of the spoken language, when known. `run` fills it in.
+    pub language: Option<String>,
+}
+
+impl Default for PipelineRequest {
+    fn default() -> Self {
+        Self {
+            mode: RecordingMode::Dictation,
+            context: String::new(),
+            style: "default".to_string(),
+            language: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PipelineResult {
+    /// True when the audio was too short or silent and nothing was transcribed.
+    pub skipped: bool,
+    /// Whisper output before replacements and LLM processing.
+    pub raw_text: String,
+    /// Final text, ready to paste.
+    pub text: String,
+    /// Name of the speech engine, e.g. "local" or "groq".
+    pub engine: String,
+    pub local: bool,
+    /// Name of the LLM provider that processed the text, if any.
+    pub enhancer: Option<String>,
+    /// Segments and timing behind `raw_text`. Empty when only `process` ran.
+    pub transcript: Transcript,
+}
+
+/// Samples to transcribe, with the VAD spans they were cut from if trimmed.
+pub(crate) type Trimmed<'s> = (Cow<'s, [f32]>, Option<Vec<audio::SpeechSpan>>);
+
+/// Headless dictation pipeline: transcription → text replacements → optional
+/// LLM processing. Holds no audio or UI state; callers pass samples in and
+/// decide what to do with the result (paste, print, save).
+#[derive(Clone, Copy)]
+pub struct Pipeline<'a> {
+    settings: &'a Settings,
+    engine: Option<&'a dyn SpeechEngine>,
+    word_timestamps: bool,
+    translate: bool,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(settings: &'a Settings) -> Self {
+        Self {
+            settings,
+            engine: None,
+            word_timestamps: false,
+            translate: false,
+        }
+    }
+
+    /// Transcribe with `engine`, typically the loaded local Whisper model.
+    /// Without one, the remote engine selected in settings is used.
+    pub fn with_engine(mut self, engine: &'a dyn SpeechEngine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Ask the engine for per-word timing, e.g. for subtitles.
+    pub fn with_word_timestamps(mut self, enabled: bool) -> Self {
+        self.word_timestamps = enabled;
+        self
+    }
+
+    /// Have Whisper translate the speech into English instead of
+    /// transcribing it. `run` turns this on for `SpeechToEnglish`.
+    pub fn with_translation(mut self, enabled: bool) -> Self {
+        self.translate = enabled;
+        self
+    }
+
+    /// Returns true if transcription goes to a remote engine (Groq or a
+    /// custom server) instead of the local one.
+    pub fn uses_remote_engine(&self) -> bool {
+        self.engine.is_none() && !self.settings.uses_local_engine()
+    }
+
+    /// Language of `transcript`: the one fixed in settings, else the detected
+    /// one. Always English when translating.
+    pub fn spoken_language(&self, transcript: &Transcript) -> Option<String> {
+        if self.translate {
+            return Some("en".to_string());
+        }
+        match self.settings.whisper_language() {
+            "auto" => transcript.language.as_ref().map(|l| l.code.clone()),
+            code => Some(code.to_string()),
+        }
+    }
+
+    /// Name and locality of the engine `transcribe` will use.
+    fn engine_identity(&self) -> (String, bool) {
+        match self.engine {
+            Some(engine) => (engine.name().to_string(), engine.is_local()),
+            None => engine::create_remote_engine(self.settings)
+                .map(|e| (e.name().to_string(), e.is_local()))
+                .unwrap_or_else(|| ("local".to_string(), true)),
+        }
+    }
+
+    /// Run the full pipeline on 16kHz mono samples.
+    pub fn run(
+        &self,
+        samples: &[f32],
+        request: &PipelineRequest,
+        observer: &dyn PipelineObserver,
+    ) -> Result<PipelineResult, PipelineError> {
+        // Anti-hallucination: skip if audio is too short or silent (applies to all engines)
+        if !audio::is_audio_usable(samples) {
+            let (engine, local) = self.engine_identity();
+            return Ok(PipelineResult {
+                skipped: true,
+                engine,
+                local,
+                ..Default::default()
+            });
+        }
+
+        observer.stage(Stage::Transcribing);
+        let pipeline = self.with_translation(self.translate || request.mode == RecordingMode::SpeechToEnglish);
+        let transcript = pipeline.transcribe(samples, observer)?;
+        let request = PipelineRequest {
+            language: request.language.clone().or_else(|| pipeline.spoken_language(&transcript)),
+            ..request.clone()
+        };
+        let mut result = self.process(&transcript.text(), &request, observer)?;
+        result.transcript = transcript;
+        Ok(result)
+    }
+
+    /// Transcribe samples with the configured engine. `text()` of the
+    /// result is the raw Whisper text; timestamps refer to `samples` even
+    /// when VAD trimmed silence before transcription.
+    pub fn transcribe(
+        &self,
+        samples: &[f32],
+        observer: &dyn PipelineObserver,
+    ) -> Result<Transcript, PipelineError> {
+        let Some((samples, spans)) = trim_silence(samples, self.settings.vad_enabled) else {
+            return Ok(Transcript::default());
+        };
+        self.with_resolved_engine(|engine| {
+            let mut transcript = self.with_options(|options| engine.transcribe(&samples, options))?;
+            if let Some(spans) = spans {
+                transcript.map_times(|ms| source_time_ms(&spans, ms));
+            }
+            Ok(self.report(engine, transcript, observer))
+        })
+    }
+
+    /// Feed live-preview `stream` the recording so far, decoding only audio
+    /// after its committed segments. Needs `with_engine`.
+    pub fn update_stream(
+        &self,
+        stream: &mut StreamingTranscriber,
+        recording: &AudioSnapshot,
+    ) -> Result<Transcript, PipelineError> {
+        let engine = self.engine.ok_or(PipelineError::EngineNotLoaded)?;
+        stream.vad = self.settings.vad_enabled;
+        Ok(self.with_options(|options| stream.update(engine, recording, options))?)
+    }
+
+    /// Like `transcribe`, but reuses the segments `stream` already committed
+    /// during live preview so only the end of the recording is decoded.
+    /// Needs `with_engine`.
+    pub fn finish_stream(
+        &self,
+        mut stream: StreamingTranscriber,
+        recording: &AudioSnapshot,
+        observer: &dyn PipelineObserver,
+    ) -> Result<Transcript, PipelineError> {
+        let engine = self.engine.ok_or(PipelineError::EngineNotLoaded)?;
+        stream.vad = self.settings.vad_enabled;
+        let transcript = self.with_options(|options| stream.finish(engine, recording, options))?;
+        Ok(self.report(engine, transcript, observer))
+    }
+
+    /// Transcribe the chunks of a long-form recording that are complete,
+    /// `recording` being the audio so far. Returns the text so far.
+    pub fn update_long_form(
+        &self,
+        long_form: &mut LongFormTranscriber,
+        recording: &AudioSnapshot,
+    ) -> Result<Transcript, PipelineError> {
+        self.with_resolved_engine(|engine| {
+            self.with_options(|options| long_form.update(engine, recording, options))?;
+            Ok(long_form.preview())
+        })
+    }
+
+    /// Transcribe the rest of a long-form recording. Returns the whole
+    /// transcript and the path of the retained audio, if any.
+    pub fn finish_long_form(
+        &self,
+        long_form: LongFormTranscriber,
+        recording: &AudioSnapshot,
+        observer: &dyn PipelineObserver,
+    ) -> Result<(Transcript, Option<PathBuf>), PipelineError> {
+        self.with_resolved_engine(|engine| {
+            let (transcript, path) = self.with_options(|options| long_form.finish(engine, recording, options))?;
+            Ok((self.report(engine, transcript, observer), path))
+        })
+    }
+
+    /// Run `f` with the engine to transcribe with: the one passed to
+    /// `with_engine`, else the remote engine selected in settings.
+    fn with_resolved_engine<R>(
+        &self,
+        f: impl FnOnce(&dyn SpeechEngine) -> Result<R, PipelineError>,
+    ) -> Result<R, PipelineError> {
+        let remote;
+        let engine = match self.engine {
+            Some(engine) => engine,
+            None => {
+                remote = engine::create_remote_engine(self.settings)
+                    .ok_or(PipelineError::EngineNotLoaded)?;
+                remote.as_ref()
+            }
+        };
+        if self.translate && !engine.is_local() {
+            return Err(PipelineError::TranslationNeedsLocal);
+        }
+        f(engine)
+    }
+
+    /// Run `f` with transcription options built from settings.
+    fn with_options<R>(&self, f: impl FnOnce(&TranscribeOptions) -> R) -> R {
+        let initial_prompt = self.settings.whisper_initial_prompt();
+        let auto_languages = self.settings.auto_detect_languages();
+        let filter = HallucinationFilter::new(&self.settings.hallucination_filter);
+        f(&TranscribeOptions {
+            allowed_languages: &auto_languages,
+            word_timestamps: self.word_timestamps,
+            decoding: self.settings.decoding(),
+            translate: self.translate,
+            hallucination_filter: &filter,
+            ..TranscribeOptions::new(self.settings.whisper_language(), &initial_prompt)
+        })
+    }
+
+    /// Final clean-up of an engine's transcript, reported to `observer`.
+    fn report(
+        &self,
+        engine: &dyn SpeechEngine,
+        mut transcript: Transcript,
+        observer: &dyn PipelineObserver,
+    ) -> Transcript {
+        repetition::remove_loops(&mut transcript);
+
+        observer.engine_info(engine.name(), engine.is_local(), transcript.language.as_ref());
+        if let Some(rejection) = &transcript.rejection {
+            observer.rejected(rejection);
+        }
+        log::debug!("[whisper raw] {}", transcript.text());
+        transcript
+    }
+
+    /// Turn raw Whisper text into the final text for `request.mode`.
+    ///
+    /// Dictation applies text replacements, then the optional LLM cleanup
+    /// (LLM failures fall back to the replaced text). VoiceCommand and
+    /// ClipboardRewrite skip replacements and require an LLM. SpeechToEnglish
+    /// text is Whisper's translation, used as is.
+    pub fn process(
+        &self,
+        raw_text: &str,
+        request: &PipelineRequest,
+        observer: &dyn PipelineObserver,
+    ) -> Result<PipelineResult, PipelineError> {
+        let (engine, local) = self.engine_identity();
+        let mut result = PipelineResult {
+            skipped: false,
+            raw_text: raw_text.to_string(),
+            text: String::new(),
+            engine,
+            local,
+            enhancer: None,
+            transcript: Transcript::default(),
+        };
+
+        result.text = match request.mode {
+            RecordingMode::Dictation => {
+                let replaced = self.settings.apply_replacements(raw_text);
+                match llm::create_enhancer(self.settings) {
+                    Some(enhancer) if !replaced.is_empty() => {
+                        observer.stage(Stage::Processing);
+                        observer.enhancer_info(enhancer.name(), enhancer.is_local());
+                        result.enhancer = Some(enhancer.name().to_string());
+
+                        match enhancer.enhance(&replaced, &request.style, request.language.as_deref()) {
+                            Ok(processed) => {
+                                log::debug!("[llm output] {}", processed);
+                                processed
+                            }
+                            Err(e) => {
+                                log::error!("LLM post-processing failed: {}", e);
+                                observer.warning(&format!(
+                                    "LLM processing failed, using raw text: {e}"
+                                ));
+                                replaced
+                            }
+                        }
+                    }
+                    _ => replaced,
+                }
+            }
+            RecordingMode::VoiceCommand | RecordingMode::ClipboardRewrite => {
+                if raw_text.trim().is_empty() {
+                    return Ok(result);
+                }
+
+                observer.stage(Stage::Processing);
+                let enhancer =
+                    llm::create_enhancer(self.settings).ok_or(PipelineError::EnhancerRequired)?;
+                observer.enhancer_info(enhancer.name(), enhancer.is_local());
+                result.enhancer = Some(enhancer.name().to_string());
+
+                match enhancer.execute_command(raw_text, &request.context, request.mode.context_type()) {
+                    Ok(output) => {
+                        log::debug!("[llm command output] {}", output);
+                        output
+                    }
+                    Err(e) => {
+                        log::error!("LLM execute_command failed: {}", e);
+                        return Err(e.into());
+                    }
+                }
+            }
+            RecordingMode::SpeechToEnglish => raw_text.to_string(),
+            // Translate mode doesn't go through the recording pipeline,
+            // but if it somehow does, just return the raw text.
+            RecordingMode::Translate => raw_text.to_string(),
+        };
+
+        Ok(result)
+    }
+}
+
+/// Drop silence outside the VAD speech spans. Returns `None` when the
+/// audio has no speech at all, and the samples unchanged when VAD is off
+/// or trimming would leave a clip too short to transcribe reliably.
+/// The spans are returned when the samples were trimmed.
+pub(crate) fn trim_silence(samples: &[f32], vad_enabled: bool) -> Option<Trimmed<'_>> {
+    if !vad_enabled {
+        return Some((Cow::Borrowed(samples), None));
+    }
+
+    let spans = audio::detect_speech(samples);
+    if spans.is_empty() {
+        log::info!("VAD found no speech in {} samples, skipping transcription", samples.len());
+        return None;
+    }
+
+    let speech = audio::extract_speech(samples, &spans);
+    log::debug!(
+        "VAD kept {} of {} samples in {} span(s)",
+        speech.len(),
+        samples.len(),
+        spans.len()
+    );
+    if speech.len() < audio::MIN_TRANSCRIBE_SAMPLES {
+        return Some((Cow::Borrowed(samples), None));
+    }
+    Some((Cow::Owned(speech), Some(spans)))
+}
+
+/// Map a time in the concatenated speech spans back to the original clip.
+pub(crate) fn source_time_ms(spans: &[audio::SpeechSpan], ms: u64) -> u64 {
+    let sample = (ms * audio::TARGET_SAMPLE_RATE as u64 / 1000) as usize;
+    let mut offset = 0;
+    for span in spans {
+        if sample < offset + span.len() {
+            return audio::samples_to_ms(span.start + sample - offset);
+        }
+        offset += span.len();
+    }
+    spans.last().map_or(ms, |span| audio::samples_to_ms(span.end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MockEngine;
+    use crate::settings::TextReplacement;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        stages: RefCell<Vec<Stage>>,
+        engines: RefCell<Vec<(String, bool)>>,
+    }
+
+    impl PipelineObserver for RecordingObserver {
+        fn stage(&self, stage: Stage) {
+            self.stages.borrow_mut().push(stage);
+        }
+
+        fn engine_info(&self, name: &str, local: bool, _language: Option<&DetectedLanguage>) {
+            self.engines.borrow_mut().push((name.to_string(), local));
+        }
+    }
+
+    #[test]
+    fn test_run_skips_silent_audio() {
+        let settings = Settings::default();
+        let observer = RecordingObserver::default();
+        let result = Pipeline::new(&settings)
+            .run(&vec![0.0; 32_000], &PipelineRequest::default(), &observer)
+            .unwrap();
+        assert!(result.skipped);
+        assert!(result.text.is_empty());
+        assert!(observer.stages.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_transcribe_without_engine_fails() {
+        let settings = Settings {
+            vad_enabled: false,
+            ..Settings::default()
+        };
+        let err = Pipeline::new(&settings)
+            .transcribe(&vec![0.1; 32_000], &())
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::EngineNotLoaded));
+    }
+
+    #[test]
+    fn test_transcribe_skips_audio_without_speech() {
+        // Loud but constant: passes the global energy gate, no speech frames
+        let settings = Settings::default();
+        let engine = MockEngine::new("thanks for watching");
+        let transcript = Pipeline::new(&settings)
+            .with_engine(&engine)
+            .transcribe(&vec![0.1; 32_000], &())
+            .unwrap();
+        assert!(transcript.is_empty());
+        assert!(engine.calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_source_time_skips_trimmed_silence() {
+        // Speech at 1.0–2.0s and 3.0–3.5s
+        let spans = [
+            audio::SpeechSpan { start: 16_000, end: 32_000 },
+            audio::SpeechSpan { start: 48_000, end: 56_000 },
+        ];
+        assert_eq!(source_time_ms(&spans, 0), 1000);
+        assert_eq!(source_time_ms(&spans, 500), 1500);
+        assert_eq!(source_time_ms(&spans, 1000), 3000);
+        assert_eq!(source_time_ms(&spans, 1250), 3250);
+        assert_eq!(source_time_ms(&spans, 9000), 3500);
+    }
+
+    #[test]
+    fn test_run_with_mock_engine() {
+        let settings = Settings {
+            vad_enabled: false,
+            text_replacements: vec![TextReplacement {
+                find: "GPT".to_string(),
+                replace: "LLM".to_string(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        let engine = MockEngine::new("ask GPT");
+        let observer = RecordingObserver::default();
+        let result = Pipeline::new(&settings)
+            .with_engine(&engine)
+            .run(&vec![0.1; 32_000], &PipelineRequest::default(), &observer)
+            .unwrap();
+        assert!(!result.skipped);
+        assert_eq!(result.raw_text, "ask GPT");
+        assert_eq!(result.text, "ask LLM");
+        assert_eq!(result.transcript.text(), "ask GPT");
+        assert_eq!(result.transcript.segments[0].end_ms, 2000);
+        assert_eq!(result.engine, "mock");
+        assert!(result.local);
+        assert_eq!(*engine.calls.lock().unwrap(), vec![32_000]);
+        assert_eq!(*observer.stages.borrow(), vec![Stage::Transcribing]);
+        assert_eq!(*observer.engines.borrow(), vec![("mock".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_stream_with_mock_engine() {
+        let settings = Settings {
+            vad_enabled: false,
+            ..Default::default()
+        };
+        let mut stream = StreamingTranscriber::new();
+        let err = Pipeline::new(&settings)
+            .update_stream(&mut stream, &AudioSnapshot::from(vec![0.1; 32_000]))
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::EngineNotLoaded));
+
+        let engine = MockEngine::new("hello world");
+        let observer = RecordingObserver::default();
+        let pipeline = Pipeline::new(&settings).with_engine(&engine);
+        let preview = pipeline
+            .update_stream(&mut stream, &AudioSnapshot::from(vec![0.1; 32_000]))
+            .unwrap();
+        assert_eq!(preview.text(), "hello world");
+        // A single segment is never committed, so the final pass decodes it all
+        let transcript = pipeline
+            .finish_stream(stream, &AudioSnapshot::from(vec![0.1; 48_000]), &observer)
+            .unwrap();
+        assert_eq!(transcript.text(), "hello world");
+        assert_eq!(*engine.calls.lock().unwrap(), vec![32_000, 48_000]);
+        assert_eq!(*observer.engines.borrow(), vec![("mock".to_string(), true)]);
+    }
+
+    /// Engine that hears two sentences in every clip, splitting it in half.
+    #[derive(Default)]
+    struct TwoSentenceEngine {
+        calls: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl SpeechEngine for TwoSentenceEngine {
+        fn name(&self) -> &str {
+            "two-sentence"
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn transcribe(&self, samples: &[f32], _options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+            self.calls.lock().unwrap().push(samples.len());
+            let half_ms = audio::samples_to_ms(samples.len() / 2);
+            let mut transcript = Transcript::from_text(" One.", half_ms);
+            let mut second = Transcript::from_text(" Two.", half_ms);
+            second.map_times(|ms| ms + half_ms);
+            transcript.segments.extend(second.segments);
+            Ok(transcript)
+        }
+    }
+
+    #[test]
+    fn test_finish_stream_trims_trailing_silence() {
+        let settings = Settings::default();
+        let engine = TwoSentenceEngine::default();
+        let pipeline = Pipeline::new(&settings).with_engine(&engine);
+        let speech: Vec<f32> = (0..96_000).map(|i| 0.3 * (i as f32 * 0.19).sin()).collect();
+        let mut stream = StreamingTranscriber::new();
+        pipeline.update_stream(&mut stream, &speech[..64_000].to_vec().into()).unwrap();
+        pipeline.update_stream(&mut stream, &speech.clone().into()).unwrap();
+        assert_eq!(stream.committed_samples(), 48_000);
+
+        // Four seconds of silence after the speech are not sent to the engine
+        let mut recording = speech;
+        recording.resize(160_000, 0.0);
+        let transcript = pipeline.finish_stream(stream, &recording.into(), &()).unwrap();
+        // The rest of the speech plus 200ms of VAD padding
+        let tail = 48_000 + 3_200;
+        assert_eq!(*engine.calls.lock().unwrap(), vec![64_000, 96_000, tail]);
+        assert!(transcript.segments.last().unwrap().end_ms <= audio::samples_to_ms(48_000 + tail));
+
+        // A tail with no speech at all is not decoded
+        let mut stream = StreamingTranscriber::new();
+        pipeline.update_stream(&mut stream, &vec![0.0; 64_000].into()).unwrap();
+        let transcript = pipeline.finish_stream(stream, &vec![0.0; 96_000].into(), &()).unwrap();
+        assert!(transcript.is_empty());
+        assert_eq!(engine.calls.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_long_form_with_mock_engine() {
+        let settings = Settings::default();
+        let speech = |len: usize| -> AudioSnapshot {
+            (0..len).map(|i| 0.3 * (i as f32 * 0.19).sin()).collect::<Vec<_>>().into()
+        };
+        let mut long_form = LongFormTranscriber::new();
+        let err = Pipeline::new(&settings)
+            .update_long_form(&mut long_form, &speech(32_000))
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::EngineNotLoaded));
+
+        let engine = MockEngine::new("hello world");
+        let observer = RecordingObserver::default();
+        let pipeline = Pipeline::new(&settings).with_engine(&engine);
+        // Too little audio for a chunk yet
+        let preview = pipeline.update_long_form(&mut long_form, &speech(32_000)).unwrap();
+        assert!(preview.segments.is_empty());
+
+        let (transcript, path) = pipeline
+            .finish_long_form(long_form, &speech(48_000), &observer)
+            .unwrap();
+        assert_eq!(transcript.text(), "hello world");
+        assert!(path.is_none());
+        assert_eq!(*engine.calls.lock().unwrap(), vec![48_000]);
+        assert_eq!(*observer.engines.borrow(), vec![("mock".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_spoken_language() {
+        let mut transcript = Transcript::from_text("hola", 1000);
+        transcript.language = Some(DetectedLanguage {
+            code: "es".to_string(),
+            probability: 0.9,
+        });
+        let auto = Settings::default();
+        assert_eq!(Pipeline::new(&auto).spoken_language(&transcript).as_deref(), Some("es"));
+        assert_eq!(Pipeline::new(&auto).spoken_language(&Transcript::default()), None);
+        let fixed = Settings {
+            language: "en".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(Pipeline::new(&fixed).spoken_language(&transcript).as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_speech_to_english() {
+        let settings = Settings {
+            vad_enabled: false,
+            text_replacements: vec![TextReplacement {
+                find: "hello".to_string(),
+                replace: "bye".to_string(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        let request = PipelineRequest {
+            mode: RecordingMode::SpeechToEnglish,
+            ..PipelineRequest::default()
+        };
+        let engine = MockEngine::new("hello");
+        let result = Pipeline::new(&settings)
+            .with_engine(&engine)
+            .run(&vec![0.1; 32_000], &request, &())
+            .unwrap();
+        // Whisper's translation is used as is
+        assert_eq!(result.text, "hello");
+        assert_eq!(*engine.translate_calls.lock().unwrap(), vec![true]);
+        assert_eq!(
+            Pipeline::new(&settings).with_translation(true).spoken_language(&result.transcript).as_deref(),
+            Some("en")
+        );
+
+        let remote = MockEngine {
+            local: false,
+            ..MockEngine::new("hello")
+        };
+        let err = Pipeline::new(&settings)
+            .with_engine(&remote)
+            .run(&vec![0.1; 32_000], &request, &())
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::TranslationNeedsLocal));
+    }
+
+    #[test]
+    fn test_explicit_engine_overrides_settings() {
+        // A loaded engine wins even if settings select a remote one
+        let settings = Settings {
+            engine: "groq".to_string(),
+            groq_api_key: "gsk_test".to_string(),
+            vad_enabled: false,
+            ..Settings::default()
+        };
+        let engine = MockEngine {
+            local: false,
+            ..MockEngine::new("hello")
+        };
+        let pipeline = Pipeline::new(&settings).with_engine(&engine);
+        assert!(!pipeline.uses_remote_engine());
+        let result = pipeline
+            .run(&vec![0.1; 32_000], &PipelineRequest::default(), &())
+            .unwrap();
+        assert_eq!(result.engine, "mock");
+        assert!(!result.local);
+    }
+
+    #[test]
+    fn test_uses_remote_engine_requires_config() {
+        let mut settings = Settings {
+            engine: "groq".to_string(),
+            ..Settings::default()
+        };
+        assert!(!Pipeline::new(&settings).uses_remote_engine());
+        settings.groq_api_key = "gsk_test".to_string();
+        assert!(Pipeline::new(&settings).uses_remote_engine());
+
+        settings.engine = "custom".to_string();
+        assert!(!Pipeline::new(&settings).uses_remote_engine());
+        settings.custom_engine_url = "http://localhost:8000".to_string();
+        assert!(Pipeline::new(&settings).uses_remote_engine());
+    }
+
+    #[test]
+    fn test_process_dictation_applies_replacements() {
+        let settings = Settings {
+            text_replacements: vec![TextReplacement {
+                find: "GPT".to_string(),
+                replace: "LLM".to_string(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        let observer = RecordingObserver::default();
+        let result = Pipeline::new(&settings)
+            .process("ask GPT", &PipelineRequest::default(), &observer)
+            .unwrap();
+        assert_eq!(result.raw_text, "ask GPT");
+        assert_eq!(result.text, "ask LLM");
+        assert!(result.enhancer.is_none());
+        // No LLM configured → no processing stage
+        assert!(observer.stages.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_process_voice_command_requires_enhancer() {
+        let settings = Settings::default();
+        let request = PipelineRequest {
+            mode: RecordingMode::VoiceCommand,
+            context: "hey what's up".to_string(),
+            ..PipelineRequest::default()
+        };
+        let observer = RecordingObserver::default();
+        let err = Pipeline::new(&settings)
+            .process("make this formal", &request, &observer)
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::EnhancerRequired));
+        assert_eq!(*observer.stages.borrow(), vec![Stage::Processing]);
+    }
+
+    #[test]
+    fn test_process_voice_command_empty_is_noop() {
+        let settings = Settings::default();
+        let request = PipelineRequest {
+            mode: RecordingMode::ClipboardRewrite,
+            ..PipelineRequest::default()
+        };
+        let result = Pipeline::new(&settings).process("  ", &request, &()).unwrap();
+        assert!(result.text.is_empty());
+    }
+}
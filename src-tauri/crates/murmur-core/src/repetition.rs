@@ -0,0 +1,280 @@
+// Detects Whisper's repetition loops ("and then and then and then…") in a
+// transcript and truncates them to a single occurrence. Loops show up both
+// inside one segment and as the same segment emitted over and over.
+
+use crate::transcript::{is_cjk, text_key, Segment, Transcript};
+
+/// Longest phrase, in words, checked for repetition.
+const MAX_PHRASE_WORDS: usize = 8;
+/// Consecutive occurrences of a phrase that make a loop. Single words need
+/// more, so emphasis like "no no no" survives.
+const MIN_PHRASE_REPEATS: usize = 3;
+const MIN_WORD_REPEATS: usize = 5;
+/// Consecutive identical segments that make a loop.
+const MIN_SEGMENT_REPEATS: usize = 3;
+
+/// A loop removed from a transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepetitionLoop {
+    /// Index in `Transcript::segments` of the first occurrence.
+    pub segment: usize,
+    /// The repeated text, as it first appeared.
+    pub phrase: String,
+    /// Occurrences found; all but one were dropped.
+    pub repeats: usize,
+}
+
+/// Truncate repetition loops in `transcript`: repeated phrases within a
+/// segment are cut to their last occurrence, which carries the closing
+/// punctuation, and repeated segments after the first are marked skipped.
+/// Word timings of a truncated segment are trimmed to match.
+/// Rejected transcripts are left alone.
+pub fn remove_loops(transcript: &mut Transcript) -> Vec<RepetitionLoop> {
+    if transcript.rejection.is_some() {
+        return Vec::new();
+    }
+    let mut loops = remove_segment_loops(&mut transcript.segments);
+    for (index, segment) in transcript.segments.iter_mut().enumerate() {
+        if !segment.skipped {
+            loops.extend(truncate_segment(segment, index));
+        }
+    }
+    for found in &loops {
+        log::info!(
+            "truncated repetition loop in segment {}: {:?} x{}",
+            found.segment,
+            found.phrase.trim(),
+            found.repeats
+        );
+    }
+    loops
+}
+
+/// Mark runs of identical kept segments as skipped, except the first.
+fn remove_segment_loops(segments: &mut [Segment]) -> Vec<RepetitionLoop> {
+    let kept: Vec<usize> = (0..segments.len()).filter(|&i| !segments[i].skipped).collect();
+    let mut loops = Vec::new();
+    let mut start = 0;
+    while start < kept.len() {
+        let key = text_key(&segments[kept[start]].text);
+        let run = kept[start..]
+            .iter()
+            .take_while(|&&i| text_key(&segments[i].text) == key)
+            .count();
+        if run >= MIN_SEGMENT_REPEATS && !key.is_empty() {
+            for &i in &kept[start + 1..start + run] {
+                segments[i].skipped = true;
+            }
+            loops.push(RepetitionLoop {
+                segment: kept[start],
+                phrase: segments[kept[start]].text.clone(),
+                repeats: run,
+            });
+        }
+        start += run;
+    }
+    loops
+}
+
+/// Collapse every repeated phrase in one segment to a single occurrence.
+fn truncate_segment(segment: &mut Segment, index: usize) -> Vec<RepetitionLoop> {
+    let units = split_units(&segment.text);
+    let keys: Vec<String> = units.iter().map(|u| text_key(u)).collect();
+    let mut keep = vec![true; units.len()];
+    let mut loops = Vec::new();
+
+    let mut i = 0;
+    while i < units.len() {
+        match find_loop(&keys[i..]) {
+            Some((len, repeats)) => {
+                keep[i..i + len * (repeats - 1)].fill(false);
+                loops.push(RepetitionLoop {
+                    segment: index,
+                    phrase: units[i..i + len].concat(),
+                    repeats,
+                });
+                i += len * repeats;
+            }
+            None => i += 1,
+        }
+    }
+    if loops.is_empty() {
+        return loops;
+    }
+
+    segment.text = units
+        .iter()
+        .zip(&keep)
+        .filter(|(_, &k)| k)
+        .map(|(u, _)| u.as_str())
+        .collect();
+    if segment.words.len() == units.len() {
+        let mut keep = keep.iter();
+        segment.words.retain(|_| *keep.next().unwrap_or(&true));
+    } else if !segment.words.is_empty() {
+        // Words don't line up with the text units; stale timing is worse than none
+        segment.words.clear();
+    }
+    loops
+}
+
+/// Phrase length and repeat count of a loop starting at `keys[0]`.
+fn find_loop(keys: &[String]) -> Option<(usize, usize)> {
+    (1..=MAX_PHRASE_WORDS.min(keys.len() / 2)).find_map(|len| {
+        let phrase = &keys[..len];
+        if phrase.iter().all(String::is_empty) {
+            return None;
+        }
+        let repeats = keys.chunks_exact(len).take_while(|chunk| *chunk == phrase).count();
+        let min = if len == 1 { MIN_WORD_REPEATS } else { MIN_PHRASE_REPEATS };
+        (repeats >= min).then_some((len, repeats))
+    })
+}
+
+/// Split text into words, each with its leading whitespace so the pieces
+/// concatenate back to the original. CJK characters are words on their own.
+fn split_units(text: &str) -> Vec<String> {
+    let mut units: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut in_space = false;
+    for c in text.chars() {
+        let starts_unit = match prev {
+            None => true,
+            Some(p) if c.is_whitespace() => !p.is_whitespace(),
+            Some(p) => !in_space && (is_cjk(c) || is_cjk(p)),
+        };
+        if starts_unit {
+            units.push(String::new());
+        }
+        in_space = c.is_whitespace();
+        if let Some(unit) = units.last_mut() {
+            unit.push(c);
+        }
+        prev = Some(c);
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::{Rejection, Word};
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            avg_prob: 0.9,
+            no_speech_prob: 0.0,
+            skipped: false,
+            words: Vec::new(),
+        }
+    }
+
+    fn transcript(texts: &[&str]) -> Transcript {
+        Transcript {
+            segments: texts
+                .iter()
+                .enumerate()
+                .map(|(i, t)| segment(t, i as u64 * 1000, (i as u64 + 1) * 1000))
+                .collect(),
+            ..Transcript::default()
+        }
+    }
+
+    #[test]
+    fn test_split_units_round_trips() {
+        let text = " Hello,  world 你好。ok";
+        let units = split_units(text);
+        assert_eq!(units, vec![" Hello,", "  world", " 你", "好", "。ok"]);
+        assert_eq!(units.concat(), text);
+    }
+
+    #[test]
+    fn test_phrase_loop_within_segment() {
+        let mut t = transcript(&[" So I went home and then and then and then and then and then."]);
+        let loops = remove_loops(&mut t);
+        assert_eq!(t.text(), "So I went home and then.");
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].phrase, " and then");
+        assert_eq!(loops[0].repeats, 5);
+    }
+
+    #[test]
+    fn test_loop_keeps_trailing_text() {
+        let mut t = transcript(&[" I think, I think, I think, I think we should go."]);
+        remove_loops(&mut t);
+        assert_eq!(t.text(), "I think we should go.");
+    }
+
+    #[test]
+    fn test_cjk_loop() {
+        let mut t = transcript(&["我們今天我們今天我們今天我們今天"]);
+        let loops = remove_loops(&mut t);
+        assert_eq!(t.text(), "我們今天");
+        assert_eq!(loops[0].repeats, 4);
+    }
+
+    #[test]
+    fn test_short_repeats_survive() {
+        let texts = [
+            " No, no, no, that's wrong.",
+            " It was very, very good.",
+            " Bye bye.",
+            " The the meeting is at three.",
+        ];
+        for text in texts {
+            let mut t = transcript(&[text]);
+            assert!(remove_loops(&mut t).is_empty(), "{text}");
+            assert_eq!(t.segments[0].text, text);
+        }
+    }
+
+    #[test]
+    fn test_repeated_segments() {
+        let mut t = transcript(&[" Let's start.", " Thank you.", " Thank you.", " thank you", " Thank you.", " Next item."]);
+        let loops = remove_loops(&mut t);
+        assert_eq!(t.text(), "Let's start. Thank you. Next item.");
+        assert_eq!(loops, vec![RepetitionLoop { segment: 1, phrase: " Thank you.".to_string(), repeats: 4 }]);
+        assert_eq!(t.segments.len(), 6);
+
+        // Two in a row is ordinary speech
+        let mut t = transcript(&[" Yes.", " Yes."]);
+        assert!(remove_loops(&mut t).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_segments_ignore_skipped() {
+        let mut t = transcript(&[" Okay.", " Okay.", " ...", " Okay."]);
+        t.segments[2].skipped = true;
+        remove_loops(&mut t);
+        assert_eq!(t.text(), "Okay.");
+    }
+
+    #[test]
+    fn test_truncation_trims_words() {
+        let mut t = transcript(&[" go on go on go on go on"]);
+        t.segments[0].words = (0..8)
+            .map(|i| Word {
+                text: if i % 2 == 0 { "go" } else { "on" }.to_string(),
+                start_ms: i * 100,
+                end_ms: i * 100 + 90,
+                prob: 0.8,
+            })
+            .collect();
+        remove_loops(&mut t);
+        let segment = &t.segments[0];
+        assert_eq!(segment.text, " go on");
+        assert_eq!(segment.words.len(), 2);
+        assert_eq!(segment.words[0].start_ms, 600);
+    }
+
+    #[test]
+    fn test_rejected_transcript_untouched() {
+        let mut t = transcript(&[" la la la la la la"]);
+        t.rejection = Some(Rejection::LowConfidence { avg_prob: 0.1 });
+        assert!(remove_loops(&mut t).is_empty());
+        assert_eq!(t.segments[0].text, " la la la la la la");
+    }
+}
@@ -0,0 +1,354 @@
+// Band-limited sample rate conversion for microphones that can't run at 16kHz.
+//
+// Polyphase windowed-sinc: each output sample is a dot product of the input
+// around its position with one of a precomputed set of filter phases. The
+// filter cuts off just below the lower Nyquist frequency, so 44.1/48kHz input
+// doesn't fold high frequencies back into the speech band.
+
+/// Filter zero crossings on each side of the center. Longer filters give a
+/// sharper transition band at the cost of more taps per output sample.
+const ZERO_CROSSINGS: f64 = 16.0;
+/// Cutoff as a fraction of the output Nyquist frequency.
+const ROLLOFF: f64 = 0.92;
+/// Kaiser window shape; 8.6 gives roughly 85dB stopband attenuation.
+const KAISER_BETA: f64 = 8.6;
+/// Upper bound on precomputed filter phases. Rate pairs with a larger
+/// reduced numerator round to the nearest phase.
+const MAX_PHASES: usize = 1024;
+
+/// Streaming resampler. Keeps the input tail between `process` calls, so
+/// audio split across cpal callbacks resamples exactly as if it were one
+/// buffer.
+pub struct Resampler {
+    /// Output rate / input rate, reduced: `up` output samples per `down` inputs.
+    up: usize,
+    down: usize,
+    phases: usize,
+    /// Taps on each side of the center; each phase has `2 * half_taps` taps.
+    half_taps: usize,
+    /// `phases` rows of `2 * half_taps` coefficients.
+    bank: Vec<f32>,
+    /// Unconsumed input, starting `half_taps - 1` samples before `pos`.
+    buffer: Vec<f32>,
+    /// Input index of the next output sample, relative to `buffer`.
+    pos: usize,
+    /// Fractional part of the next output position, in units of `1 / up`.
+    frac: usize,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        let g = gcd(input_rate as usize, output_rate as usize);
+        let up = output_rate as usize / g;
+        let down = input_rate as usize / g;
+        let phases = up.min(MAX_PHASES);
+
+        // Cutoff in cycles per input sample
+        let cutoff = 0.5 * (up as f64 / down as f64).min(1.0) * ROLLOFF;
+        let half_taps = (ZERO_CROSSINGS / (2.0 * cutoff)).ceil() as usize;
+        let taps = 2 * half_taps;
+
+        let mut bank = Vec::with_capacity(phases * taps);
+        for phase in 0..phases {
+            let frac = phase as f64 / phases as f64;
+            let start = bank.len();
+            for j in 0..taps {
+                // Distance from the output position to input sample
+                // `pos - half_taps + 1 + j`
+                let t = frac + half_taps as f64 - 1.0 - j as f64;
+                bank.push(kaiser_sinc(t, cutoff, half_taps as f64) as f32);
+            }
+            // Unity gain at DC for every phase
+            let sum: f32 = bank[start..].iter().sum();
+            for c in &mut bank[start..] {
+                *c /= sum;
+            }
+        }
+
+        Self {
+            up,
+            down,
+            phases,
+            half_taps,
+            bank,
+            buffer: vec![0.0; half_taps - 1],
+            pos: half_taps - 1,
+            frac: 0,
+        }
+    }
+
+    /// Resamples `input` and appends the result to `output`. Output lags
+    /// input by `half_taps` samples until `flush`.
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        self.buffer.extend_from_slice(input);
+        let taps = 2 * self.half_taps;
+        output.reserve(input.len() * self.up / self.down + 1);
+
+        while self.pos + self.half_taps < self.buffer.len() {
+            let phase = self.frac * self.phases / self.up;
+            let coeffs = &self.bank[phase * taps..(phase + 1) * taps];
+            let window = &self.buffer[self.pos + 1 - self.half_taps..=self.pos + self.half_taps];
+            output.push(window.iter().zip(coeffs).map(|(x, c)| x * c).sum());
+
+            self.frac += self.down;
+            self.pos += self.frac / self.up;
+            self.frac %= self.up;
+        }
+
+        // Keep only the history the next output needs
+        let keep_from = self.pos + 1 - self.half_taps;
+        if keep_from > 0 {
+            let keep_from = keep_from.min(self.buffer.len());
+            self.buffer.drain(..keep_from);
+            self.pos -= keep_from;
+        }
+    }
+
+    /// Emits the samples still held back for look-ahead and resets the
+    /// resampler for a new stream.
+    pub fn flush(&mut self, output: &mut Vec<f32>) {
+        let consumed = self.pos;
+        let remaining_inputs = self.buffer.len().saturating_sub(consumed);
+        let expected = (remaining_inputs * self.up).div_ceil(self.down);
+        let start = output.len();
+        self.process(&vec![0.0; self.half_taps], output);
+        output.truncate(start + expected.min(output.len() - start));
+
+        self.buffer = vec![0.0; self.half_taps - 1];
+        self.pos = self.half_taps - 1;
+        self.frac = 0;
+    }
+}
+
+/// One-shot conversion of a complete buffer.
+pub fn resample(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
+    if input_rate == output_rate {
+        return input.to_vec();
+    }
+    let mut resampler = Resampler::new(input_rate, output_rate);
+    let mut output = Vec::new();
+    resampler.process(input, &mut output);
+    resampler.flush(&mut output);
+    output
+}
+
+/// Kaiser-windowed low-pass sinc at offset `t` input samples.
+fn kaiser_sinc(t: f64, cutoff: f64, half_width: f64) -> f64 {
+    let x = t / half_width;
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let arg = 2.0 * cutoff * t;
+    let sinc = if arg.abs() < 1e-12 {
+        1.0
+    } else {
+        (std::f64::consts::PI * arg).sin() / (std::f64::consts::PI * arg)
+    };
+    let window = bessel_i0(KAISER_BETA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_BETA);
+    2.0 * cutoff * sinc * window
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series).
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_sq = x * x / 4.0;
+    for k in 1..50 {
+        term *= half_sq / (k * k) as f64;
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The linear-interpolation resampler this module replaced, kept as a
+    /// baseline for the aliasing comparison.
+    fn resample_linear_into(input: &[f32], ratio: f64, output: &mut Vec<f32>) {
+        if input.is_empty() {
+            return;
+        }
+
+        let output_len = (input.len() as f64 * ratio).ceil() as usize;
+        output.reserve(output_len);
+
+        let inv_ratio = 1.0 / ratio; // avoid f64 division in hot loop
+
+        let safe_output_len = if input.len() > 1 {
+            let len = ((input.len() - 1) as f64 * ratio).floor() as usize;
+            len.min(output_len)
+        } else {
+            0
+        };
+
+        // Hot path: guaranteed src_idx + 1 < input.len()
+        for i in 0..safe_output_len {
+            let src_pos = i as f64 * inv_ratio;
+            let src_idx = src_pos as usize;
+            let frac = (src_pos - src_idx as f64) as f32;
+
+            output.push(input[src_idx] * (1.0 - frac) + input[src_idx + 1] * frac);
+        }
+
+        // Tail path: handles boundary cases
+        for i in safe_output_len..output_len {
+            let src_pos = i as f64 * inv_ratio;
+            let src_idx = src_pos as usize;
+            let frac = (src_pos - src_idx as f64) as f32;
+
+            let sample = if src_idx + 1 < input.len() {
+                input[src_idx] * (1.0 - frac) + input[src_idx + 1] * frac
+            } else if src_idx < input.len() {
+                input[src_idx]
+            } else {
+                0.0
+            };
+
+            output.push(sample);
+        }
+    }
+
+    fn tone(freq: f64, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f64::consts::PI * freq * i as f64 / rate as f64).sin() as f32 * 0.5)
+            .collect()
+    }
+
+    /// Amplitude of `freq` in `samples` (Goertzel), ignoring the edges.
+    fn amplitude_at(samples: &[f32], freq: f64, rate: u32) -> f64 {
+        let body = &samples[samples.len() / 8..samples.len() * 7 / 8];
+        let w = 2.0 * std::f64::consts::PI * freq / rate as f64;
+        let coeff = 2.0 * w.cos();
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in body {
+            let s0 = x as f64 + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+        2.0 * power.sqrt() / body.len() as f64
+    }
+
+    #[test]
+    fn test_resample_linear_into_identity() {
+        let input = vec![0.0, 0.5, 1.0];
+        let mut output = Vec::new();
+        resample_linear_into(&input, 1.0, &mut output);
+
+        assert_eq!(output.len(), 3);
+        assert!((output[0] - 0.0).abs() < 1e-6);
+        assert!((output[1] - 0.5).abs() < 1e-6);
+        assert!((output[2] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_resample_linear_into_upsample() {
+        let input = vec![0.0, 1.0];
+        // Ratio 2.0 -> output len 4
+        let mut output = Vec::new();
+        resample_linear_into(&input, 2.0, &mut output);
+
+        assert_eq!(output.len(), 4);
+        assert!((output[0] - 0.0).abs() < 1e-6); // idx 0
+        assert!((output[1] - 0.5).abs() < 1e-6); // idx 0.5
+        assert!((output[2] - 1.0).abs() < 1e-6); // idx 1.0
+        // idx 1.5 -> src_idx 1. (idx+1 out of bounds). input[1] = 1.0.
+        // 1.0 * (1-0.5) + (out_of_bounds? no, logic says if src_idx < len returns input[src_idx])
+        // wait, logic says:
+        // if src_idx + 1 < input.len() { lerp }
+        // else if src_idx < input.len() { input[src_idx] }
+        // else { 0.0 }
+
+        // i=3. src_pos = 1.5. src_idx=1. frac=0.5.
+        // src_idx+1 = 2. input len is 2. 2 < 2 is false.
+        // src_idx < input.len() -> 1 < 2 is true.
+        // returns input[1] which is 1.0.
+        assert!((output[3] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_resample_linear_into_downsample() {
+        let input = vec![0.0, 0.5, 1.0, 0.5];
+        // Ratio 0.5 -> output len 2
+        let mut output = Vec::new();
+        resample_linear_into(&input, 0.5, &mut output);
+
+        assert_eq!(output.len(), 2);
+        // i=0. pos=0. input[0]=0.0
+        assert!((output[0] - 0.0).abs() < 1e-6);
+
+        // i=1. pos=2. input[2]=1.0
+        assert!((output[1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_resample_output_length() {
+        for rate in [8_000, 22_050, 44_100, 48_000, 96_000] {
+            let output = resample(&vec![0.0; rate as usize], rate, 16_000);
+            assert_eq!(output.len(), 16_000, "from {rate}Hz");
+        }
+    }
+
+    #[test]
+    fn test_resample_preserves_passband() {
+        for rate in [44_100, 48_000] {
+            let output = resample(&tone(1_000.0, rate, rate as usize), rate, 16_000);
+            let amp = amplitude_at(&output, 1_000.0, 16_000);
+            assert!((amp - 0.5).abs() < 0.01, "{rate}Hz: 1kHz amplitude {amp}");
+        }
+    }
+
+    #[test]
+    fn test_resample_aliasing_vs_linear() {
+        // A 10kHz tone is above the 8kHz output Nyquist and must be removed.
+        // Decimating folds it to 6kHz; linear interpolation lets most of it through.
+        for rate in [44_100, 48_000] {
+            let input = tone(10_000.0, rate, rate as usize);
+
+            let sinc = resample(&input, rate, 16_000);
+            let mut linear = Vec::new();
+            resample_linear_into(&input, 16_000.0 / rate as f64, &mut linear);
+
+            let sinc_alias = amplitude_at(&sinc, 6_000.0, 16_000);
+            let linear_alias = amplitude_at(&linear, 6_000.0, 16_000);
+
+            // At least 60dB below the 0.5 input tone, and 40dB better than linear
+            assert!(sinc_alias < 0.5e-3, "{rate}Hz: sinc alias {sinc_alias}");
+            assert!(sinc_alias * 100.0 < linear_alias, "{rate}Hz: sinc {sinc_alias} vs linear {linear_alias}");
+        }
+    }
+
+    #[test]
+    fn test_resample_chunked_matches_whole() {
+        let input = tone(440.0, 44_100, 44_100);
+
+        let mut whole = Vec::new();
+        let mut resampler = Resampler::new(44_100, 16_000);
+        resampler.process(&input, &mut whole);
+
+        // Uneven callback sizes, like a real audio device
+        let mut chunked = Vec::new();
+        let mut resampler = Resampler::new(44_100, 16_000);
+        for chunk in input.chunks(441).flat_map(|c| c.chunks(137)) {
+            resampler.process(chunk, &mut chunked);
+        }
+
+        assert_eq!(whole.len(), chunked.len());
+        for (a, b) in whole.iter().zip(&chunked) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,864 @@
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::hallucination::FilterSettings;
+use crate::state::RecordingMode;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_hotkey_dictation() -> String {
+    "left_option".to_string()
+}
+
+fn default_hotkey_translate() -> String {
+    "AltLeft+KeyT".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextReplacement {
+    pub find: String,
+    pub replace: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Whisper decoding parameters for local transcription. Chosen through
+/// `Settings::accuracy_profile`; the stored values only apply to "custom".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Decoding {
+    /// 1 decodes greedily; more runs beam search with this many beams.
+    pub beam_size: u32,
+    /// Candidates sampled per fallback temperature when decoding greedily.
+    pub best_of: u32,
+    /// Temperature added on each fallback retry; 0 disables fallback.
+    pub temperature_inc: f32,
+    /// A segment whose token entropy exceeds this is retried (or dropped).
+    pub entropy_threshold: f32,
+    /// A segment whose average log probability is below this is retried.
+    pub logprob_threshold: f32,
+    /// Segments more likely than this to be silence are skipped.
+    pub no_speech_threshold: f32,
+    /// Results with a lower average token probability are discarded.
+    pub confidence_threshold: f32,
+    /// Decoder threads; 0 picks from the CPU count.
+    pub threads: u32,
+}
+
+/// Accuracy profiles offered in settings, besides "custom".
+pub const ACCURACY_PROFILES: &[&str] = &["fast", "balanced", "accurate"];
+
+/// whisper.cpp runs at most this many decoders at once.
+const MAX_DECODERS: u32 = 8;
+
+impl Default for Decoding {
+    /// The "fast" profile: greedy, no temperature fallback. Fallback mostly
+    /// produces hallucinations on dictation-length clips.
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            best_of: 1,
+            temperature_inc: 0.0,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+            confidence_threshold: 0.4,
+            threads: 0,
+        }
+    }
+}
+
+impl Decoding {
+    /// Parameters for a named profile, `None` for "custom" or unknown names.
+    pub fn preset(profile: &str) -> Option<Self> {
+        match profile {
+            "fast" => Some(Self::default()),
+            // Greedy, retrying uncertain segments at higher temperatures
+            "balanced" => Some(Self {
+                best_of: 5,
+                temperature_inc: 0.2,
+                ..Self::default()
+            }),
+            // Beam search with fallback; roughly 2-3x slower than "fast"
+            "accurate" => Some(Self {
+                beam_size: 5,
+                best_of: 5,
+                temperature_inc: 0.2,
+                ..Self::default()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Check every value is in a range whisper.cpp accepts, naming the
+    /// first one that isn't.
+    pub fn validate(&self) -> Result<(), String> {
+        fn check(name: &str, ok: bool, range: &str) -> Result<(), String> {
+            if ok {
+                Ok(())
+            } else {
+                Err(format!("{name} must be {range}"))
+            }
+        }
+        check("beam size", (1..=MAX_DECODERS).contains(&self.beam_size), "between 1 and 8")?;
+        check("best of", (1..=MAX_DECODERS).contains(&self.best_of), "between 1 and 8")?;
+        check("temperature increment", (0.0..=1.0).contains(&self.temperature_inc), "between 0 and 1")?;
+        check("entropy threshold", (0.0..=10.0).contains(&self.entropy_threshold), "between 0 and 10")?;
+        check("log probability threshold", (-10.0..=0.0).contains(&self.logprob_threshold), "between -10 and 0")?;
+        check("no-speech threshold", (0.0..=1.0).contains(&self.no_speech_threshold), "between 0 and 1")?;
+        check("confidence threshold", (0.0..=1.0).contains(&self.confidence_threshold), "between 0 and 1")?;
+        check("threads", self.threads <= 64, "at most 64 (0 for automatic)")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Legacy field — kept for backward compatibility with old settings files.
+    // New code should use hotkey_dictation instead.
+    #[serde(skip_serializing)]
+    pub ptt_key: String,
+    pub language: String,
+    /// Candidates when `language` is "auto"; empty lets Whisper pick any
+    /// language. Short clips are often misdetected without this.
+    pub auto_languages: Vec<String>,
+    pub engine: String,
+    pub model: String,
+    /// Base URL serving ggml model files by name, used instead of Hugging
+    /// Face when set (e.g. an internal artifact server).
+    pub model_mirror_url: String,
+    /// "fast", "balanced", "accurate" or "custom" (uses `decoding`).
+    pub accuracy_profile: String,
+    pub decoding: Decoding,
+    /// Changes to the built-in hallucination filter rules.
+    pub hallucination_filter: FilterSettings,
+    /// Input device name; empty means the system default.
+    pub input_device: String,
+    /// Keep the microphone open between recordings and prepend the last
+    /// `pre_roll_ms` of audio, so the first syllable isn't clipped. Opt-in.
+    pub pre_roll_enabled: bool,
+    pub pre_roll_ms: u32,
+    pub groq_api_key: String,
+    /// OpenAI-compatible transcription server used when `engine` is "custom".
+    pub custom_engine_url: String,
+    pub custom_engine_key: String,
+    pub custom_engine_model: String,
+    pub window_opacity: f64,
+    pub auto_start: bool,
+    pub onboarding_complete: bool,
+    /// "hold", "toggle", or "long_form": toggle that transcribes as it
+    /// goes, for meetings and lectures.
+    pub recording_mode: String,
+    /// Long-form recordings stop after this many minutes; 0 for no limit.
+    pub long_form_max_minutes: u32,
+    /// Save long-form recordings as WAV files in the temp directory instead
+    /// of discarding the audio once it's transcribed.
+    pub long_form_keep_audio: bool,
+    /// Record each result in the local history database. Off until the
+    /// user turns it on.
+    pub history_enabled: bool,
+    /// Folder for the history database; empty for the app data directory.
+    pub history_dir: String,
+    /// Delete history entries older than this many days; 0 keeps them all.
+    pub history_retention_days: u32,
+    pub dictionary: String,
+    pub llm_enabled: bool,
+    pub llm_model: String,
+    pub llm_provider: String,
+    pub ollama_url: String,
+    pub ollama_model: String,
+    pub custom_llm_url: String,
+    pub custom_llm_key: String,
+    pub custom_llm_model: String,
+    pub app_aware_style: bool,
+    /// Trim silence with voice activity detection before transcription.
+    pub vad_enabled: bool,
+    pub ui_locale: String,
+    // Legacy field — kept for backward compatibility. Use hotkey_translate instead.
+    #[serde(skip_serializing)]
+    pub translate_hotkey: String,
+    pub translate_language: String,
+    pub dictionary_packs: Vec<String>,
+    #[serde(default)]
+    pub text_replacements: Vec<TextReplacement>,
+
+    // --- Multi-mode hotkey fields (v0.5.0+) ---
+    #[serde(default = "default_hotkey_dictation")]
+    pub hotkey_dictation: String,
+    #[serde(default = "default_hotkey_translate")]
+    pub hotkey_translate: String,
+    #[serde(default)]
+    pub hotkey_voice_command: String,
+    #[serde(default)]
+    pub hotkey_clipboard_rewrite: String,
+    #[serde(default)]
+    pub hotkey_speech_to_english: String,
+}
+
+/// Toggle recordings stop after this long, in case the second press is
+/// forgotten.
+const TOGGLE_TIME_LIMIT: Duration = Duration::from_secs(300);
+
+/// Whisper language codes selectable in settings, besides "auto".
+pub const LANGUAGES: &[&str] = &[
+    "zh", "en", "ja", "ko", "fr", "de", "es", "pt", "ru", "ar", "hi", "th", "vi", "id",
+];
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ptt_key: String::new(),
+            language: "auto".to_string(),
+            auto_languages: Vec::new(),
+            engine: "local".to_string(),
+            model: "large-v3-turbo".to_string(),
+            model_mirror_url: String::new(),
+            accuracy_profile: "fast".to_string(),
+            decoding: Decoding::default(),
+            hallucination_filter: FilterSettings::default(),
+            input_device: String::new(),
+            pre_roll_enabled: false,
+            pre_roll_ms: 500,
+            groq_api_key: String::new(),
+            custom_engine_url: String::new(),
+            custom_engine_key: String::new(),
+            custom_engine_model: "whisper-1".to_string(),
+            window_opacity: 0.78,
+            auto_start: false,
+            onboarding_complete: false,
+            recording_mode: "hold".to_string(),
+            long_form_max_minutes: 0,
+            long_form_keep_audio: false,
+            history_enabled: false,
+            history_dir: String::new(),
+            history_retention_days: 90,
+            dictionary: String::new(),
+            llm_enabled: false,
+            llm_model: "llama-3.3-70b-versatile".to_string(),
+            llm_provider: "groq".to_string(),
+            ollama_url: "http://localhost:11434".to_string(),
+            ollama_model: "llama3.2".to_string(),
+            custom_llm_url: String::new(),
+            custom_llm_key: String::new(),
+            custom_llm_model: String::new(),
+            app_aware_style: true,
+            vad_enabled: true,
+            ui_locale: "en".to_string(),
+            translate_hotkey: String::new(),
+            translate_language: "en".to_string(),
+            dictionary_packs: Vec::new(),
+            text_replacements: Vec::new(),
+            hotkey_dictation: "left_option".to_string(),
+            hotkey_translate: "AltLeft+KeyT".to_string(),
+            hotkey_voice_command: String::new(),
+            hotkey_clipboard_rewrite: String::new(),
+            hotkey_speech_to_english: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Migrate legacy field names to new hotkey fields.
+    /// Called after deserialization — if legacy fields have values but new fields
+    /// have defaults, copy the legacy values over.
+    pub fn migrate_legacy_hotkeys(&mut self) {
+        // ptt_key → hotkey_dictation
+        if !self.ptt_key.is_empty() && self.hotkey_dictation == default_hotkey_dictation() {
+            self.hotkey_dictation = self.ptt_key.clone();
+        }
+        // translate_hotkey → hotkey_translate
+        if !self.translate_hotkey.is_empty() && self.hotkey_translate == default_hotkey_translate() {
+            self.hotkey_translate = self.translate_hotkey.clone();
+        }
+    }
+
+    /// Apply text replacement rules to the given text.
+    pub fn apply_replacements(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for rule in &self.text_replacements {
+            if rule.enabled && !rule.find.is_empty() {
+                result = result.replace(&rule.find, &rule.replace);
+            }
+        }
+        result
+    }
+
+    pub fn is_long_form(&self) -> bool {
+        self.recording_mode == "long_form"
+    }
+
+    /// How long a `mode` recording may run before it's stopped automatically.
+    /// Only Dictation is transcribed in chunks in long-form mode; other
+    /// modes hold the whole recording, so they keep the toggle limit.
+    pub fn recording_time_limit(&self, mode: RecordingMode) -> Option<Duration> {
+        match self.recording_mode.as_str() {
+            "toggle" => Some(TOGGLE_TIME_LIMIT),
+            "long_form" if mode != RecordingMode::Dictation => Some(TOGGLE_TIME_LIMIT),
+            "long_form" if self.long_form_max_minutes > 0 => {
+                Some(Duration::from_secs(u64::from(self.long_form_max_minutes) * 60))
+            }
+            _ => None,
+        }
+    }
+
+    /// Where the history database lives.
+    pub fn history_path(&self, app_data_dir: &Path) -> PathBuf {
+        let dir = match self.history_dir.trim() {
+            "" => app_data_dir,
+            dir => Path::new(dir),
+        };
+        dir.join(crate::history::HISTORY_FILE)
+    }
+
+    /// How long history entries are kept, if not forever.
+    pub fn history_retention(&self) -> Option<Duration> {
+        (self.history_retention_days > 0).then(|| Duration::from_secs(u64::from(self.history_retention_days) * 86_400))
+    }
+
+    /// True when transcription runs on the local Whisper model — either it
+    /// was selected, or the selected remote engine is missing its config.
+    pub fn uses_local_engine(&self) -> bool {
+        crate::engine::create_remote_engine(self).is_none()
+    }
+
+    /// Returns the whisper language code.
+    pub fn whisper_language(&self) -> &str {
+        LANGUAGES
+            .iter()
+            .find(|code| **code == self.language)
+            .copied()
+            .unwrap_or("auto")
+    }
+
+    /// `auto_languages` without codes Murmur doesn't offer.
+    pub fn auto_detect_languages(&self) -> Vec<String> {
+        self.auto_languages
+            .iter()
+            .filter(|code| LANGUAGES.contains(&code.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Decoding parameters for the selected accuracy profile. Invalid custom
+    /// values (e.g. a hand-edited settings.json) fall back to "fast".
+    pub fn decoding(&self) -> Decoding {
+        if let Some(preset) = Decoding::preset(&self.accuracy_profile) {
+            return preset;
+        }
+        if self.accuracy_profile != "custom" {
+            log::warn!("unknown accuracy profile {:?}, using fast", self.accuracy_profile);
+            return Decoding::default();
+        }
+        match self.decoding.validate() {
+            Ok(()) => self.decoding,
+            Err(e) => {
+                log::warn!("invalid custom decoding settings ({e}), using fast");
+                Decoding::default()
+            }
+        }
+    }
+
+    /// Reject settings that can't be applied. Only checks what the settings
+    /// window lets users type freely.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.accuracy_profile == "custom" {
+            self.decoding.validate()?;
+        } else if !ACCURACY_PROFILES.contains(&self.accuracy_profile.as_str()) {
+            return Err(format!("unknown accuracy profile: {}", self.accuracy_profile));
+        }
+        self.hallucination_filter.validate()
+    }
+
+    /// Builds the full initial_prompt for Whisper, combining language bias,
+    /// enabled dictionary packs, and user custom dictionary.
+    ///
+    /// Whisper limits initial_prompt to ~224 tokens (max_initial_prompt_tokens).
+    /// Order matters: Whisper weighs tokens closest to the audio (end of prompt)
+    /// more heavily. We put dictionary terms first (can be truncated) and
+    /// language bias + custom dictionary last (most important).
+    pub fn whisper_initial_prompt(&self) -> String {
+        let mut parts = Vec::new();
+
+        // Dictionary pack terms first (lowest priority, truncated first by Whisper)
+        for pack in &self.dictionary_packs {
+            if let Some(content) = dict_pack_content(pack) {
+                let terms: String = content
+                    .lines()
+                    .flat_map(|line| line.split(','))
+                    .map(|t| t.trim())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if !terms.is_empty() {
+                    parts.push(terms);
+                }
+            }
+        }
+
+        // Custom dictionary (higher priority than packs)
+        if !self.dictionary.is_empty() {
+            parts.push(self.dictionary.clone());
+        }
+
+        // Language bias last (highest priority — closest to audio)
+        if self.language == "zh" || self.language == "auto" {
+            parts.push("繁體中文語音轉錄，使用台灣正體中文。".to_string());
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// Returns the embedded content of a built-in dictionary pack.
+fn dict_pack_content(name: &str) -> Option<&'static str> {
+    match name {
+        "crypto" => Some(include_str!("../../../../src/dictionaries/crypto.txt")),
+        "ai-ml" => Some(include_str!("../../../../src/dictionaries/ai-ml.txt")),
+        "dev-tools" => Some(include_str!("../../../../src/dictionaries/dev-tools.txt")),
+        _ => None,
+    }
+}
+
+fn settings_path(base: &Path) -> PathBuf {
+    base.join("settings.json")
+}
+
+pub fn load_settings(base: &Path) -> Settings {
+    let path = settings_path(base);
+    let mut settings = match std::fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::error!("failed to parse settings.json, using defaults: {e}");
+                Settings::default()
+            }
+        },
+        Err(_) => Settings::default(),
+    };
+    settings.migrate_legacy_hotkeys();
+    settings
+}
+
+pub fn save_settings(settings: &Settings, base: &Path) -> Result<(), String> {
+    let path = settings_path(base);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_legacy_settings() {
+        let json = r#"{
+            "ptt_key": "AltLeft",
+            "language": "auto",
+            "engine": "local",
+            "model": "large-v3-turbo",
+            "groq_api_key": "gsk_test",
+            "window_opacity": 0.78,
+            "auto_start": false,
+            "llm_enabled": true,
+            "llm_model": "llama-3.3-70b-versatile"
+        }"#;
+
+        let mut s: Settings = serde_json::from_str(json).unwrap();
+        s.migrate_legacy_hotkeys();
+        assert_eq!(s.llm_provider, "groq");
+        assert_eq!(s.ollama_url, "http://localhost:11434");
+        assert_eq!(s.ollama_model, "llama3.2");
+        assert!(s.custom_llm_url.is_empty());
+        // Legacy ptt_key should migrate to hotkey_dictation
+        assert_eq!(s.hotkey_dictation, "AltLeft");
+    }
+
+    #[test]
+    fn test_deserialize_without_ui_locale() {
+        let json = r#"{
+            "ptt_key": "AltLeft",
+            "language": "auto",
+            "engine": "local",
+            "model": "large-v3-turbo",
+            "groq_api_key": "",
+            "window_opacity": 0.78,
+            "auto_start": false
+        }"#;
+
+        let s: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(s.ui_locale, "en");
+    }
+
+    #[test]
+    fn test_recording_time_limit() {
+        let dictation = RecordingMode::Dictation;
+        let mut s = Settings::default();
+        assert_eq!(s.recording_time_limit(dictation), None);
+
+        s.recording_mode = "toggle".to_string();
+        assert_eq!(s.recording_time_limit(dictation), Some(Duration::from_secs(300)));
+        assert_eq!(s.recording_time_limit(RecordingMode::VoiceCommand), Some(Duration::from_secs(300)));
+
+        s.recording_mode = "long_form".to_string();
+        assert_eq!(s.recording_time_limit(dictation), None);
+        s.long_form_max_minutes = 90;
+        assert_eq!(s.recording_time_limit(dictation), Some(Duration::from_secs(5_400)));
+    }
+
+    #[test]
+    fn test_long_form_limit_only_for_dictation() {
+        let s = Settings {
+            recording_mode: "long_form".to_string(),
+            ..Default::default()
+        };
+        for mode in [
+            RecordingMode::VoiceCommand,
+            RecordingMode::ClipboardRewrite,
+            RecordingMode::Translate,
+            RecordingMode::SpeechToEnglish,
+        ] {
+            assert_eq!(s.recording_time_limit(mode), Some(Duration::from_secs(300)), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_history_settings() {
+        let mut s = Settings::default();
+        let data = Path::new("/data");
+        assert!(!s.history_enabled);
+        assert_eq!(s.history_path(data), data.join("history.db"));
+        assert_eq!(s.history_retention(), Some(Duration::from_secs(90 * 86_400)));
+
+        s.history_dir = "/elsewhere ".to_string();
+        s.history_retention_days = 0;
+        assert_eq!(s.history_path(data), Path::new("/elsewhere").join("history.db"));
+        assert_eq!(s.history_retention(), None);
+    }
+
+    #[test]
+    fn test_input_device_roundtrip() {
+        let s: Settings = serde_json::from_str(r#"{"language": "en"}"#).unwrap();
+        assert!(s.input_device.is_empty());
+
+        let s = Settings {
+            input_device: "USB Headset".to_string(),
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&s).unwrap();
+        let restored: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.input_device, "USB Headset");
+    }
+
+    #[test]
+    fn test_deserialize_new_settings() {
+        let json = r#"{
+            "ptt_key": "AltLeft",
+            "language": "auto",
+            "engine": "local",
+            "model": "large-v3-turbo",
+            "groq_api_key": "",
+            "window_opacity": 0.78,
+            "auto_start": false,
+            "llm_enabled": true,
+            "llm_model": "llama-3.3-70b-versatile",
+            "llm_provider": "ollama",
+            "ollama_url": "http://192.168.1.100:11434",
+            "ollama_model": "mistral"
+        }"#;
+
+        let s: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(s.llm_provider, "ollama");
+        assert_eq!(s.ollama_url, "http://192.168.1.100:11434");
+        assert_eq!(s.ollama_model, "mistral");
+    }
+
+    #[test]
+    fn test_whisper_language_and_auto_candidates() {
+        let s = Settings {
+            language: "xx".to_string(),
+            auto_languages: vec!["en".to_string(), "klingon".to_string(), "zh".to_string()],
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_language(), "auto");
+        assert_eq!(s.auto_detect_languages(), vec!["en", "zh"]);
+        let ja = Settings {
+            language: "ja".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(ja.whisper_language(), "ja");
+    }
+
+    #[test]
+    fn test_decoding_profiles() {
+        let s = Settings::default();
+        assert_eq!(s.decoding(), Decoding::default());
+        assert!(s.validate().is_ok());
+        for profile in ACCURACY_PROFILES {
+            assert!(Decoding::preset(profile).unwrap().validate().is_ok(), "{profile}");
+        }
+        assert_eq!(Decoding::preset("accurate").unwrap().beam_size, 5);
+
+        let custom = Decoding {
+            beam_size: 3,
+            threads: 2,
+            ..Decoding::default()
+        };
+        let s = Settings {
+            accuracy_profile: "custom".to_string(),
+            decoding: custom,
+            ..Settings::default()
+        };
+        assert_eq!(s.decoding(), custom);
+
+        // Out-of-range custom values are rejected on save and ignored on load
+        let s = Settings {
+            accuracy_profile: "custom".to_string(),
+            decoding: Decoding {
+                beam_size: 0,
+                ..Decoding::default()
+            },
+            ..Settings::default()
+        };
+        assert_eq!(s.validate().unwrap_err(), "beam size must be between 1 and 8");
+        assert_eq!(s.decoding(), Decoding::default());
+
+        let s = Settings {
+            accuracy_profile: "turbo".to_string(),
+            ..Settings::default()
+        };
+        assert!(s.validate().is_err());
+        assert_eq!(s.decoding(), Decoding::default());
+    }
+
+    #[test]
+    fn test_decoding_partial_json() {
+        let s: Settings = serde_json::from_str(r#"{"accuracy_profile": "custom", "decoding": {"beam_size": 4}}"#).unwrap();
+        assert_eq!(s.decoding().beam_size, 4);
+        assert_eq!(s.decoding().no_speech_threshold, 0.6);
+    }
+
+    #[test]
+    fn test_whisper_initial_prompt_zh() {
+        let s = Settings {
+            language: "zh".to_string(),
+            dictionary: "".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_initial_prompt(), "繁體中文語音轉錄，使用台灣正體中文。");
+    }
+
+    #[test]
+    fn test_whisper_initial_prompt_auto() {
+        let s = Settings {
+            language: "auto".to_string(),
+            dictionary: "".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_initial_prompt(), "繁體中文語音轉錄，使用台灣正體中文。");
+    }
+
+    #[test]
+    fn test_whisper_initial_prompt_en() {
+        let s = Settings {
+            language: "en".to_string(),
+            dictionary: "".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_initial_prompt(), "");
+    }
+
+    #[test]
+    fn test_whisper_initial_prompt_with_dictionary() {
+        let s = Settings {
+            language: "en".to_string(),
+            dictionary: "Hello World".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_initial_prompt(), "Hello World");
+    }
+
+    #[test]
+    fn test_whisper_initial_prompt_zh_with_dictionary() {
+        let s = Settings {
+            language: "zh".to_string(),
+            dictionary: "Hello World".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(s.whisper_initial_prompt(), "Hello World 繁體中文語音轉錄，使用台灣正體中文。");
+    }
+
+    #[test]
+    fn test_deserialize_without_translate_settings() {
+        let json = r#"{
+            "ptt_key": "AltLeft",
+            "language": "auto",
+            "engine": "local",
+            "model": "large-v3-turbo",
+            "groq_api_key": "",
+            "window_opacity": 0.78,
+            "auto_start": false
+        }"#;
+        let s: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(s.hotkey_translate, "AltLeft+KeyT");
+        assert_eq!(s.translate_language, "en");
+    }
+
+    #[test]
+    fn test_apply_replacements_no_rules() {
+        let s = Settings::default();
+        assert_eq!(s.apply_replacements("hello world"), "hello world");
+    }
+
+    #[test]
+    fn test_apply_replacements_single_rule() {
+        let s = Settings {
+            text_replacements: vec![TextReplacement {
+                find: "foo".to_string(),
+                replace: "bar".to_string(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(s.apply_replacements("foo baz foo"), "bar baz bar");
+    }
+
+    #[test]
+    fn test_apply_replacements_multiple_rules() {
+        let s = Settings {
+            text_replacements: vec![
+                TextReplacement {
+                    find: "a".to_string(),
+                    replace: "b".to_string(),
+                    enabled: true,
+                },
+                TextReplacement {
+                    find: "c".to_string(),
+                    replace: "d".to_string(),
+                    enabled: true,
+                },
+            ],
+            ..Settings::default()
+        };
+        assert_eq!(s.apply_replacements("a c"), "b d");
+    }
+
+    #[test]
+    fn test_apply_replacements_disabled_rule_skipped() {
+        let s = Settings {
+            text_replacements: vec![TextReplacement {
+                find: "foo".to_string(),
+                replace: "bar".to_string(),
+                enabled: false,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(s.apply_replacements("foo"), "foo");
+    }
+
+    #[test]
+    fn test_apply_replacements_empty_find_skipped() {
+        let s = Settings {
+            text_replacements: vec![TextReplacement {
+                find: String::new(),
+                replace: "bar".to_string(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(s.apply_replacements("hello"), "hello");
+    }
+
+    #[test]
+    fn test_apply_replacements_empty_replace_deletes() {
+        let s = Settings {
+            text_replacements: vec![TextReplacement {
+                find: "remove".to_string(),
+                replace: String::new(),
+                enabled: true,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(s.apply_replacements("please remove this"), "please  this");
+    }
+
+    #[test]
+    fn test_text_replacements_serialization_roundtrip() {
+        let s = Settings {
+            text_replacements: vec![
+                TextReplacement {
+                    find: "GPT".to_string(),
+                    replace: "LLM".to_string(),
+                    enabled: true,
+                },
+                TextReplacement {
+                    find: "typo".to_string(),
+                    replace: "type".to_string(),
+                    enabled: false,
+                },
+            ],
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&s).unwrap();
+        let deserialized: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.text_replacements.len(), 2);
+        assert_eq!(deserialized.text_replacements[0].find, "GPT");
+        assert_eq!(deserialized.text_replacements[0].replace, "LLM");
+        assert!(deserialized.text_replacements[0].enabled);
+        assert_eq!(deserialized.text_replacements[1].find, "typo");
+        assert!(!deserialized.text_replacements[1].enabled);
+    }
+
+    // --- Migration tests ---
+
+    #[test]
+    fn test_migrate_legacy_ptt_key() {
+        let json = r#"{
+            "ptt_key": "right_option",
+            "language": "auto",
+            "engine": "local"
+        }"#;
+        let mut s: Settings = serde_json::from_str(json).unwrap();
+        s.migrate_legacy_hotkeys();
+        assert_eq!(s.hotkey_dictation, "right_option");
+    }
+
+    #[test]
+    fn test_migrate_legacy_translate_hotkey() {
+        let json = r#"{
+            "translate_hotkey": "MetaLeft+KeyT",
+            "language": "auto"
+        }"#;
+        let mut s: Settings = serde_json::from_str(json).unwrap();
+        s.migrate_legacy_hotkeys();
+        assert_eq!(s.hotkey_translate, "MetaLeft+KeyT");
+    }
+
+    #[test]
+    fn test_no_migration_when_new_fields_present() {
+        let json = r#"{
+            "ptt_key": "old_value",
+            "hotkey_dictation": "new_value",
+            "translate_hotkey": "old_translate",
+            "hotkey_translate": "new_translate"
+        }"#;
+        let mut s: Settings = serde_json::from_str(json).unwrap();
+        s.migrate_legacy_hotkeys();
+        // New fields should NOT be overwritten by legacy fields
+        assert_eq!(s.hotkey_dictation, "new_value");
+        assert_eq!(s.hotkey_translate, "new_translate");
+    }
+}
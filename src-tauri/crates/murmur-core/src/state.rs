@@ -2,27 +2,43 @@ use std::sync::Mutex;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub(crate) enum RecordingMode {
+pub enum RecordingMode {
     #[default]
     Dictation,
     Translate,
     VoiceCommand,
     ClipboardRewrite,
+    /// Speak any language, get English text from local Whisper. No LLM.
+    SpeechToEnglish,
 }
 
 impl RecordingMode {
     /// Returns the string identifier used in frontend events.
-    pub(crate) fn event_mode_str(&self) -> &'static str {
+    pub fn event_mode_str(&self) -> &'static str {
         match self {
             RecordingMode::Dictation => "dictated",
             RecordingMode::Translate => "translated",
             RecordingMode::VoiceCommand => "voice_command",
             RecordingMode::ClipboardRewrite => "clipboard_rewrite",
+            RecordingMode::SpeechToEnglish => "speech_to_english",
         }
     }
 
+    /// Inverse of `event_mode_str`.
+    pub fn from_event_mode_str(s: &str) -> Option<Self> {
+        [
+            RecordingMode::Dictation,
+            RecordingMode::Translate,
+            RecordingMode::VoiceCommand,
+            RecordingMode::ClipboardRewrite,
+            RecordingMode::SpeechToEnglish,
+        ]
+        .into_iter()
+        .find(|mode| mode.event_mode_str() == s)
+    }
+
     /// Returns the context_type label for LLM execute_command.
-    pub(crate) fn context_type(&self) -> &'static str {
+    pub fn context_type(&self) -> &'static str {
         match self {
             RecordingMode::VoiceCommand => "Selected text",
             RecordingMode::ClipboardRewrite => "Clipboard content",
@@ -32,7 +48,7 @@ impl RecordingMode {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
-pub(crate) enum RecordingState {
+pub enum RecordingState {
     Idle,
     Starting,
     Recording,
@@ -55,7 +71,7 @@ impl std::fmt::Display for RecordingState {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum StateError {
+pub enum StateError {
     #[error("invalid transition from {from} to {to}")]
     InvalidTransition {
         from: RecordingState,
@@ -72,22 +88,28 @@ impl serde::Serialize for StateError {
     }
 }
 
-pub(crate) struct AppState {
+pub struct AppState {
     state: Mutex<RecordingState>,
 }
 
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppState {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             state: Mutex::new(RecordingState::Idle),
         }
     }
 
-    pub(crate) fn current(&self) -> RecordingState {
+    pub fn current(&self) -> RecordingState {
         *self.state.lock().expect("state mutex poisoned")
     }
 
-    pub(crate) fn transition(&self, to: RecordingState) -> Result<RecordingState, StateError> {
+    pub fn transition(&self, to: RecordingState) -> Result<RecordingState, StateError> {
         let mut current = self.state.lock().expect("state mutex poisoned");
         if Self::is_valid_transition(*current, to) {
             *current = to;
@@ -235,6 +257,7 @@ mod tests {
             RecordingMode::Translate,
             RecordingMode::VoiceCommand,
             RecordingMode::ClipboardRewrite,
+            RecordingMode::SpeechToEnglish,
         ];
         for mode in &modes {
             let json = serde_json::to_string(mode).unwrap();
@@ -249,6 +272,9 @@ mod tests {
         assert_eq!(RecordingMode::Translate.event_mode_str(), "translated");
         assert_eq!(RecordingMode::VoiceCommand.event_mode_str(), "voice_command");
         assert_eq!(RecordingMode::ClipboardRewrite.event_mode_str(), "clipboard_rewrite");
+        assert_eq!(RecordingMode::SpeechToEnglish.event_mode_str(), "speech_to_english");
+        assert_eq!(RecordingMode::from_event_mode_str("voice_command"), Some(RecordingMode::VoiceCommand));
+        assert_eq!(RecordingMode::from_event_mode_str("Dictation"), None);
     }
 
     #[test]
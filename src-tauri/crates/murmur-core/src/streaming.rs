@@ -0,0 +1,367 @@
+// Incremental transcription for live preview. Each update re-decodes only
+// the audio after the committed segments; segments two consecutive decodes
+// agree on (local agreement) are committed and never decoded again, so the
+// final pass at stop only has the last few seconds left to transcribe.
+// Only the uncommitted range of the recording snapshot is made contiguous.
+// With VAD on, silence is trimmed from that range before each decode, as in
+// a one-shot transcription.
+
+use crate::audio;
+use crate::buffer::AudioSnapshot;
+use crate::engine::{EngineError, SpeechEngine};
+use crate::pipeline::{source_time_ms, trim_silence};
+use crate::repetition;
+use crate::transcript::{text_key, DetectedLanguage, Segment, TranscribeOptions, Transcript};
+
+/// A segment must end this long before the end of the audio to be
+/// committed; words near the edge may still be cut off.
+const STABLE_MARGIN_MS: u64 = 1_000;
+/// Uncommitted audio after which all but the last segment are committed
+/// even without agreement, bounding the cost of each update (20s at 16kHz).
+const MAX_TAIL_SAMPLES: usize = 320_000;
+/// Committed text passed as prompt so the next decode continues it.
+const PROMPT_CONTEXT_CHARS: usize = 200;
+/// With "auto", the first detected language at least this likely is kept
+/// for later updates, so partial audio doesn't flicker between languages.
+const LANGUAGE_LOCK_PROB: f32 = 0.5;
+
+/// Streaming state for one recording. Feed it the growing recording with
+/// `update`, then `finish` with the complete recording.
+#[derive(Debug, Default)]
+pub struct StreamingTranscriber {
+    /// Final segments, timed from the start of the recording.
+    committed: Vec<Segment>,
+    /// Samples covered by `committed`; decoding resumes here.
+    committed_samples: usize,
+    /// Uncommitted segments from the latest decode.
+    tentative: Vec<Segment>,
+    language: Option<DetectedLanguage>,
+    /// Trim silence with VAD before decoding; set from settings by `Pipeline`.
+    pub(crate) vad: bool,
+}
+
+impl StreamingTranscriber {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True once any segment is final.
+    pub fn has_committed(&self) -> bool {
+        !self.committed.is_empty()
+    }
+
+    /// Samples at the start of the recording that won't be decoded again.
+    pub fn committed_samples(&self) -> usize {
+        self.committed_samples
+    }
+
+    /// Committed and tentative segments, for display.
+    pub fn preview(&self) -> Transcript {
+        Transcript {
+            segments: self.committed.iter().chain(&self.tentative).cloned().collect(),
+            rejection: None,
+            language: self.language.clone(),
+        }
+    }
+
+    /// Decode the uncommitted part of `recording`, the audio so far, and
+    /// commit the segments this decode and the previous one agree on.
+    /// Returns the updated preview.
+    pub fn update(
+        &mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        options: &TranscribeOptions,
+    ) -> Result<Transcript, EngineError> {
+        let Some(segments) = self.decode_tail(engine, recording, options, false)? else {
+            return Ok(self.preview());
+        };
+
+        let agreed = segments
+            .iter()
+            .zip(&self.tentative)
+            .take_while(|(a, b)| text_key(&a.text) == text_key(&b.text))
+            .count();
+        // The last segment may end mid-word however stable it looks
+        let end_ms = audio::samples_to_ms(recording.len());
+        let mut commit = segments[..agreed.min(segments.len().saturating_sub(1))]
+            .iter()
+            .take_while(|s| s.end_ms + STABLE_MARGIN_MS <= end_ms)
+            .count();
+        let tail_len = recording.len().saturating_sub(self.committed_samples);
+        if commit == 0 && tail_len > MAX_TAIL_SAMPLES && segments.len() > 1 {
+            log::info!("streaming: no agreement after {}ms, committing {} segment(s)", audio::samples_to_ms(tail_len), segments.len() - 1);
+            commit = segments.len() - 1;
+        }
+
+        self.tentative = segments;
+        if commit > 0 {
+            let committed: Vec<Segment> = self.tentative.drain(..commit).collect();
+            let end_ms = committed.last().map_or(0, |s| s.end_ms);
+            self.committed_samples = self.committed_samples.max(ms_to_samples(end_ms).min(recording.len()));
+            log::debug!("streaming: committed {} segment(s) up to {end_ms}ms", committed.len());
+            self.committed.extend(committed);
+        }
+        Ok(self.preview())
+    }
+
+    /// Decode what's left after the committed segments and return the
+    /// transcript of the whole recording.
+    pub fn finish(
+        mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        options: &TranscribeOptions,
+    ) -> Result<Transcript, EngineError> {
+        let tail = self.decode_tail(engine, recording, options, true)?.unwrap_or_default();
+        log::info!(
+            "streaming: reused {} committed segment(s), decoded last {}ms",
+            self.committed.len(),
+            audio::samples_to_ms(recording.len().saturating_sub(self.committed_samples))
+        );
+        self.committed.extend(tail);
+        Ok(Transcript {
+            segments: self.committed,
+            rejection: None,
+            language: self.language,
+        })
+    }
+
+    /// Transcribe `recording` after `committed_samples`, timed from the start
+    /// of the recording. `None` when the tail is too short to decode; with
+    /// `pad`, a short tail is padded with silence instead. A tail without
+    /// speech gives no segments and isn't decoded.
+    fn decode_tail(
+        &mut self,
+        engine: &dyn SpeechEngine,
+        recording: &AudioSnapshot,
+        options: &TranscribeOptions,
+        pad: bool,
+    ) -> Result<Option<Vec<Segment>>, EngineError> {
+        let start = self.committed_samples.min(recording.len());
+        let mut tail = recording.range(start..recording.len());
+        if pad && !tail.is_empty() && tail.len() < audio::MIN_TRANSCRIBE_SAMPLES {
+            tail.to_mut().resize(audio::MIN_TRANSCRIBE_SAMPLES, 0.0);
+        }
+        if tail.len() < audio::MIN_TRANSCRIBE_SAMPLES {
+            return Ok(None);
+        }
+
+        let Some((clip, spans)) = trim_silence(&tail, self.vad) else {
+            return Ok(Some(Vec::new()));
+        };
+
+        let prompt = context_prompt(options.initial_prompt, &self.committed);
+        let options = TranscribeOptions {
+            language: self.locked_language().unwrap_or(options.language),
+            initial_prompt: &prompt,
+            ..*options
+        };
+        let mut transcript = engine.transcribe(&clip, &options)?;
+        if let Some(rejection) = &transcript.rejection {
+            log::info!("streaming: discarded decode of last {}ms: {rejection}", audio::samples_to_ms(tail.len()));
+        }
+        if let Some(detected) = transcript.language.take() {
+            if self.locked_language().is_none() {
+                self.language = Some(detected);
+            }
+        }
+
+        let offset_ms = audio::samples_to_ms(start);
+        let end_ms = audio::samples_to_ms(recording.len());
+        transcript.map_times(|ms| {
+            let ms = spans.as_ref().map_or(ms, |spans| source_time_ms(spans, ms));
+            (ms + offset_ms).min(end_ms)
+        });
+        repetition::remove_loops(&mut transcript);
+        Ok(Some(transcript.kept_segments().cloned().collect()))
+    }
+
+    fn locked_language(&self) -> Option<&str> {
+        locked_language(self.language.as_ref())
+    }
+}
+
+/// `initial_prompt` followed by the end of the text of `segments`, so the
+/// next decode continues it.
+pub(crate) fn context_prompt(initial_prompt: &str, segments: &[Segment]) -> String {
+    let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+    let skip = text.chars().count().saturating_sub(PROMPT_CONTEXT_CHARS);
+    let context: String = text.chars().skip(skip).collect();
+    format!("{initial_prompt} {}", context.trim()).trim().to_string()
+}
+
+/// The code of `detected` if it's likely enough to keep for later decodes.
+pub(crate) fn locked_language(detected: Option<&DetectedLanguage>) -> Option<&str> {
+    detected
+        .filter(|l| l.probability >= LANGUAGE_LOCK_PROB)
+        .map(|l| l.code.as_str())
+}
+
+pub(crate) fn ms_to_samples(ms: u64) -> usize {
+    (ms * audio::TARGET_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::SampleBuffer;
+    use std::sync::Mutex;
+
+    /// Engine replaying a fixed timeline of (start_ms, end_ms, text) spoken
+    /// over the recording. Test samples hold their own index, so the engine
+    /// knows where a clip starts. Segments running past the end of the clip
+    /// come out cut short, like speech cut off mid-sentence.
+    struct TimelineEngine {
+        timeline: Vec<(u64, u64, &'static str)>,
+        calls: Mutex<Vec<(usize, usize, String)>>,
+    }
+
+    impl TimelineEngine {
+        fn new(timeline: &[(u64, u64, &'static str)]) -> Self {
+            Self {
+                timeline: timeline.to_vec(),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl SpeechEngine for TimelineEngine {
+        fn name(&self) -> &str {
+            "timeline"
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, EngineError> {
+            let start = samples[0] as usize;
+            // Padding breaks the run of indices
+            let real = samples.iter().enumerate().take_while(|&(i, &s)| s == (start + i) as f32).count();
+            self.calls.lock().unwrap().push((start, samples.len(), options.initial_prompt.to_string()));
+            let clip_start = audio::samples_to_ms(start);
+            let clip_end = audio::samples_to_ms(start + real);
+            let segments = self
+                .timeline
+                .iter()
+                .filter(|(s, _, _)| *s >= clip_start && *s < clip_end)
+                .map(|&(s, e, text)| {
+                    let text = if e > clip_end { &text[..text.len() / 2] } else { text };
+                    Segment {
+                        start_ms: s - clip_start,
+                        end_ms: e.min(clip_end) - clip_start,
+                        text: text.to_string(),
+                        avg_prob: 0.9,
+                        no_speech_prob: 0.0,
+                        skipped: false,
+                        words: Vec::new(),
+                    }
+                })
+                .collect();
+            Ok(Transcript {
+                segments,
+                ..Transcript::default()
+            })
+        }
+    }
+
+    /// The first `ms` of a recording.
+    fn recording(ms: u64) -> AudioSnapshot {
+        let mut buffer = SampleBuffer::new();
+        buffer.push(&(0..ms_to_samples(ms)).map(|i| i as f32).collect::<Vec<_>>());
+        buffer.snapshot()
+    }
+
+    const TIMELINE: &[(u64, u64, &str)] = &[
+        (0, 2_000, " First sentence."),
+        (2_000, 4_500, " Second sentence here."),
+        (4_500, 7_000, " Third one now."),
+        (7_000, 9_000, " And the last."),
+    ];
+
+    #[test]
+    fn test_commits_agreed_segments() {
+        let engine = TimelineEngine::new(TIMELINE);
+        let mut stream = StreamingTranscriber::new();
+        let options = TranscribeOptions::default();
+
+        let preview = stream.update(&engine, &recording(3_000), &options).unwrap();
+        assert_eq!(preview.text(), "First sentence. Second sen");
+        assert!(!stream.has_committed());
+
+        // Agrees on "First sentence." which ends well before the edge
+        stream.update(&engine, &recording(5_000), &options).unwrap();
+        assert_eq!(stream.committed_samples(), ms_to_samples(2_000));
+
+        let preview = stream.update(&engine, &recording(8_000), &options).unwrap();
+        assert_eq!(stream.committed_samples(), ms_to_samples(4_500));
+        assert_eq!(preview.text(), "First sentence. Second sentence here. Third one now. And th");
+
+        // Only the uncommitted tail was decoded each time
+        let calls = engine.calls.lock().unwrap();
+        let starts: Vec<usize> = calls.iter().map(|c| c.0).collect();
+        assert_eq!(starts, vec![0, 0, ms_to_samples(2_000)]);
+        assert_eq!(calls[2].2, "First sentence.");
+    }
+
+    #[test]
+    fn test_finish_decodes_only_the_tail() {
+        let engine = TimelineEngine::new(TIMELINE);
+        let mut stream = StreamingTranscriber::new();
+        let options = TranscribeOptions::new("auto", "Murmur");
+        for ms in [3_000, 5_000, 8_000] {
+            stream.update(&engine, &recording(ms), &options).unwrap();
+        }
+
+        let transcript = stream.finish(&engine, &recording(9_000), &options).unwrap();
+        assert_eq!(
+            transcript.text(),
+            "First sentence. Second sentence here. Third one now. And the last."
+        );
+        assert_eq!(transcript.segments[3].end_ms, 9_000);
+        let calls = engine.calls.lock().unwrap();
+        let last = calls.last().unwrap();
+        assert_eq!(last.0, ms_to_samples(4_500));
+        assert_eq!(last.2, "Murmur First sentence. Second sentence here.");
+    }
+
+    #[test]
+    fn test_long_tail_forces_commit() {
+        // One long first decode: nothing to agree with yet
+        let timeline = [(0, 10_000, " aaaa"), (10_000, 19_000, " bbbb"), (19_000, 30_000, " cccc")];
+        let engine = TimelineEngine::new(&timeline);
+        let mut stream = StreamingTranscriber::new();
+        let preview = stream.update(&engine, &recording(25_000), &TranscribeOptions::default()).unwrap();
+        assert_eq!(stream.committed_samples(), ms_to_samples(19_000));
+        assert_eq!(preview.text(), "aaaa bbbb c");
+    }
+
+    #[test]
+    fn test_finish_pads_short_tail() {
+        let timeline = [(0, 10_000, " aaaa"), (10_000, 20_600, " bbbb"), (20_600, 21_000, " cc.")];
+        let engine = TimelineEngine::new(&timeline);
+        let mut stream = StreamingTranscriber::new();
+        let options = TranscribeOptions::default();
+        stream.update(&engine, &recording(21_000), &options).unwrap();
+        assert_eq!(stream.committed_samples(), ms_to_samples(20_600));
+
+        let transcript = stream.finish(&engine, &recording(21_200), &options).unwrap();
+        assert_eq!(transcript.text(), "aaaa bbbb cc.");
+        assert_eq!(transcript.segments[2].end_ms, 21_000);
+        let last = engine.calls.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(last.1, audio::MIN_TRANSCRIBE_SAMPLES);
+    }
+
+    #[test]
+    fn test_empty_stream_finish() {
+        let engine = TimelineEngine::new(TIMELINE);
+        let stream = StreamingTranscriber::new();
+        let transcript = stream.finish(&engine, &recording(5_000), &TranscribeOptions::default()).unwrap();
+        assert_eq!(transcript.text(), "First sentence. Second sentence here. Third");
+    }
+}
@@ -0,0 +1,347 @@
+// Structured transcription results: segments with timing and confidence,
+// optional word timestamps, and why a result was rejected.
+
+use crate::hallucination::{FilterRule, HallucinationFilter};
+use crate::settings::Decoding;
+
+/// Input for `SpeechEngine::transcribe`.
+#[derive(Debug, Clone, Copy)]
+pub struct TranscribeOptions<'a> {
+    /// Whisper language code, or "auto" to detect.
+    pub language: &'a str,
+    /// Candidates for "auto"; empty allows every language. Local Whisper only.
+    pub allowed_languages: &'a [String],
+    pub initial_prompt: &'a str,
+    /// Fill `Segment::words`. Local Whisper only; costs a little decode time.
+    pub word_timestamps: bool,
+    /// Beam size, fallback and thresholds. Local Whisper only.
+    pub decoding: Decoding,
+    /// Output English whatever the spoken language (Whisper's translate
+    /// task). Local Whisper only.
+    pub translate: bool,
+    /// Rejects hallucinated text. Local Whisper only.
+    pub hallucination_filter: &'a HallucinationFilter,
+}
+
+impl<'a> TranscribeOptions<'a> {
+    pub fn new(language: &'a str, initial_prompt: &'a str) -> Self {
+        Self {
+            language,
+            allowed_languages: &[],
+            initial_prompt,
+            word_timestamps: false,
+            decoding: Decoding::default(),
+            translate: false,
+            hallucination_filter: HallucinationFilter::builtin(),
+        }
+    }
+}
+
+impl Default for TranscribeOptions<'_> {
+    fn default() -> Self {
+        Self::new("auto", "")
+    }
+}
+
+/// A word and its timing, in milliseconds from the start of the clip.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Word {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Lowest probability among the word's tokens.
+    pub prob: f32,
+}
+
+/// One Whisper segment, in milliseconds from the start of the clip.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// Average token probability, 1.0 when the engine doesn't report it.
+    pub avg_prob: f32,
+    pub no_speech_prob: f32,
+    /// Dropped from `Transcript::text` for a high `no_speech_prob`, or as a
+    /// repetition loop.
+    pub skipped: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+/// Language Whisper detected for a clip transcribed with "auto".
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DetectedLanguage {
+    pub code: String,
+    pub probability: f32,
+}
+
+/// Why a whole transcript was thrown away.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Rejection {
+    /// Average token probability below the confidence threshold.
+    LowConfidence { avg_prob: f32 },
+    /// Text matched a hallucination filter rule.
+    Hallucination { rule: FilterRule },
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::LowConfidence { avg_prob } => {
+                write!(f, "low confidence (average token probability {avg_prob:.2})")
+            }
+            Rejection::Hallucination { rule } => write!(f, "hallucination filter rule {rule}"),
+        }
+    }
+}
+
+/// Result of one transcription. Rejected transcripts and skipped segments
+/// are kept for debugging but contribute nothing to `text()`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct Transcript {
+    pub segments: Vec<Segment>,
+    pub rejection: Option<Rejection>,
+    /// Set when the language was auto-detected.
+    pub language: Option<DetectedLanguage>,
+}
+
+impl Transcript {
+    /// Single untimed segment spanning the clip, for engines that only
+    /// return text.
+    pub fn from_text(text: &str, duration_ms: u64) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            return Self::default();
+        }
+        Self {
+            segments: vec![Segment {
+                start_ms: 0,
+                end_ms: duration_ms,
+                text: text.to_string(),
+                avg_prob: 1.0,
+                no_speech_prob: 0.0,
+                skipped: false,
+                words: Vec::new(),
+            }],
+            rejection: None,
+            language: None,
+        }
+    }
+
+    /// Segments that make up `text()`.
+    pub fn kept_segments(&self) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(|s| !s.skipped && self.rejection.is_none())
+    }
+
+    /// The transcribed text, trimmed; empty when rejected.
+    pub fn text(&self) -> String {
+        let text: String = self.kept_segments().map(|s| s.text.as_str()).collect();
+        text.trim().to_string()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kept_segments().all(|s| s.text.trim().is_empty())
+    }
+
+    /// Rewrite every segment and word time with `f`.
+    pub fn map_times(&mut self, f: impl Fn(u64) -> u64) {
+        for segment in &mut self.segments {
+            segment.start_ms = f(segment.start_ms);
+            segment.end_ms = f(segment.end_ms);
+            for word in &mut segment.words {
+                word.start_ms = f(word.start_ms);
+                word.end_ms = f(word.end_ms);
+            }
+        }
+    }
+}
+
+/// Whisper token with timing in centiseconds, as whisper.cpp reports it.
+/// `bytes` may be part of a UTF-8 character: the tokenizer works on bytes.
+#[derive(Debug, Clone)]
+pub struct TimedToken {
+    pub bytes: Vec<u8>,
+    pub t0: i64,
+    pub t1: i64,
+    pub prob: f32,
+}
+
+/// Merge subword tokens into words. A token starting with whitespace opens
+/// a new word; CJK characters are words on their own since those scripts
+/// don't use spaces.
+pub fn group_words(tokens: &[TimedToken]) -> Vec<Word> {
+    struct Partial {
+        bytes: Vec<u8>,
+        start_ms: u64,
+        end_ms: u64,
+        prob: f32,
+    }
+
+    let mut words: Vec<Partial> = Vec::new();
+    for token in tokens {
+        if token.bytes.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let starts_word = match (std::str::from_utf8(&token.bytes), words.last()) {
+            (_, None) => true,
+            (Ok(text), Some(prev)) => match std::str::from_utf8(&prev.bytes) {
+                Ok(prev) => {
+                    text.starts_with(char::is_whitespace)
+                        || text.chars().next().is_some_and(is_cjk)
+                        || prev.chars().last().is_some_and(is_cjk)
+                }
+                // Previous token left a character unfinished
+                Err(_) => false,
+            },
+            // Continuation bytes of a split character
+            (Err(_), Some(_)) => token.bytes[0].is_ascii_whitespace(),
+        };
+        let start_ms = centis_to_ms(token.t0);
+        let end_ms = centis_to_ms(token.t1).max(start_ms);
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.bytes.extend_from_slice(&token.bytes);
+                word.end_ms = end_ms.max(word.end_ms);
+                word.prob = word.prob.min(token.prob);
+            }
+            _ => words.push(Partial {
+                bytes: token.bytes.trim_ascii_start().to_vec(),
+                start_ms,
+                end_ms,
+                prob: token.prob,
+            }),
+        }
+    }
+    words
+        .into_iter()
+        .map(|w| Word {
+            text: String::from_utf8_lossy(&w.bytes).into_owned(),
+            start_ms: w.start_ms,
+            end_ms: w.end_ms,
+            prob: w.prob,
+        })
+        .collect()
+}
+
+/// Key for comparing transcribed text: lowercase, without punctuation or
+/// whitespace, so decodes differing only in those compare equal.
+pub(crate) fn text_key(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn centis_to_ms(centis: i64) -> u64 {
+    (centis.max(0) as u64) * 10
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, t0: i64, t1: i64, prob: f32) -> TimedToken {
+        TimedToken {
+            bytes: text.as_bytes().to_vec(),
+            t0,
+            t1,
+            prob,
+        }
+    }
+
+    fn segment(text: &str, skipped: bool) -> Segment {
+        Segment {
+            start_ms: 0,
+            end_ms: 1000,
+            text: text.to_string(),
+            avg_prob: 0.9,
+            no_speech_prob: 0.0,
+            skipped,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_text_skips_dropped_segments() {
+        let transcript = Transcript {
+            segments: vec![segment(" Hello", false), segment(" Thanks for watching", true), segment(" world.", false)],
+            ..Transcript::default()
+        };
+        assert_eq!(transcript.text(), "Hello world.");
+        assert!(!transcript.is_empty());
+    }
+
+    #[test]
+    fn test_rejected_transcript_is_empty() {
+        let transcript = Transcript {
+            segments: vec![segment(" you", false)],
+            rejection: Some(Rejection::LowConfidence { avg_prob: 0.2 }),
+            ..Transcript::default()
+        };
+        assert_eq!(transcript.text(), "");
+        assert!(transcript.is_empty());
+        assert_eq!(transcript.segments.len(), 1);
+    }
+
+    #[test]
+    fn test_from_text() {
+        let transcript = Transcript::from_text("  hi there ", 2500);
+        assert_eq!(transcript.text(), "hi there");
+        assert_eq!(transcript.segments[0].end_ms, 2500);
+        assert!(Transcript::from_text("  ", 2500).segments.is_empty());
+    }
+
+    #[test]
+    fn test_group_words_latin() {
+        let words = group_words(&[
+            token(" Hel", 0, 20, 0.9),
+            token("lo", 20, 35, 0.6),
+            token(",", 35, 36, 0.99),
+            token(" world", 40, 80, 0.8),
+        ]);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello,");
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 360));
+        assert_eq!(words[0].prob, 0.6);
+        assert_eq!(words[1].text, "world");
+        assert_eq!(words[1].start_ms, 400);
+    }
+
+    #[test]
+    fn test_group_words_cjk() {
+        let words = group_words(&[token("你", 0, 10, 0.9), token("好", 10, 20, 0.9), token(" ok", 30, 40, 0.9)]);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["你", "好", "ok"]);
+
+        // "語" split across two byte-level tokens
+        let bytes = "語".as_bytes();
+        let split = [
+            TimedToken { bytes: bytes[..1].to_vec(), t0: 0, t1: 10, prob: 0.5 },
+            TimedToken { bytes: bytes[1..].to_vec(), t0: 10, t1: 20, prob: 0.7 },
+        ];
+        let words = group_words(&split);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "語");
+        assert_eq!(words[0].end_ms, 200);
+    }
+
+    #[test]
+    fn test_centis_to_ms_clamps_negative() {
+        assert_eq!(centis_to_ms(-5), 0);
+        assert_eq!(centis_to_ms(123), 1230);
+    }
+}
@@ -0,0 +1,349 @@
+use thiserror::Error;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+
+use crate::audio;
+use crate::model::LoadStage;
+use crate::settings::Decoding;
+use crate::transcript::{
+    centis_to_ms, group_words, DetectedLanguage, Rejection, Segment, TimedToken, TranscribeOptions, Transcript,
+};
+
+fn calculate_threads(available: usize) -> i32 {
+    if available <= 4 {
+        // Use all available threads if count is low (but at least 1)
+        std::cmp::max(1, available as i32)
+    } else {
+        // Reserve 2 threads for system/UI to keep app responsive
+        // Cap at 8 to avoid diminishing returns/overhead
+        let threads = available.saturating_sub(2);
+        std::cmp::min(threads, 8) as i32
+    }
+}
+
+fn optimal_threads() -> i32 {
+    let available = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    calculate_threads(available)
+}
+
+/// Thread count from the decoding settings, or the automatic choice for 0.
+fn decoding_threads(decoding: &Decoding) -> i32 {
+    match decoding.threads {
+        0 => optimal_threads(),
+        n => n as i32,
+    }
+}
+
+fn sampling_strategy(decoding: &Decoding) -> SamplingStrategy {
+    if decoding.beam_size > 1 {
+        SamplingStrategy::BeamSearch {
+            beam_size: decoding.beam_size as i32,
+            // whisper.cpp ignores patience; -1 is its default
+            patience: -1.0,
+        }
+    } else {
+        SamplingStrategy::Greedy {
+            best_of: decoding.best_of.max(1) as i32,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WhisperError {
+    #[error("failed to load whisper model: {0}")]
+    ModelLoad(String),
+    #[error("failed to create whisper state: {0}")]
+    StateCreate(String),
+    #[error("transcription failed: {0}")]
+    Transcription(String),
+}
+
+impl serde::Serialize for WhisperError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+pub struct TranscriptionEngine {
+    ctx: WhisperContext,
+}
+
+// WhisperContext is Send+Sync via its Arc<WhisperInnerContext>
+unsafe impl Send for TranscriptionEngine {}
+unsafe impl Sync for TranscriptionEngine {}
+
+impl TranscriptionEngine {
+    pub fn new(model_path: &str) -> Result<Self, WhisperError> {
+        Self::with_progress(model_path, |_| {})
+    }
+
+    /// Like `new`, reporting the `Loading` and `WarmingUp` stages as they
+    /// start. Large models spend several seconds in each.
+    pub fn with_progress(model_path: &str, on_stage: impl Fn(LoadStage)) -> Result<Self, WhisperError> {
+        on_stage(LoadStage::Loading);
+        let mut params = WhisperContextParameters::new();
+        params.use_gpu(true); // Metal (macOS) or CUDA (Windows)
+        let ctx = WhisperContext::new_with_params(model_path, params)
+            .map_err(|e| WhisperError::ModelLoad(e.to_string()))?;
+        let engine = Self { ctx };
+        engine.warmup(&on_stage)?;
+        Ok(engine)
+    }
+
+    /// Run a short dummy inference to warm up CUDA/Metal kernels.
+    /// Without this, the first real transcription is very slow due to JIT compilation.
+    fn warmup(&self, on_stage: &impl Fn(LoadStage)) -> Result<(), WhisperError> {
+        on_stage(LoadStage::WarmingUp);
+        let mut state = self
+            .ctx
+            .create_state()
+            .map_err(|e| WhisperError::StateCreate(e.to_string()))?;
+        // 1 second of silence at 16kHz
+        let dummy = vec![0.0f32; 16_000];
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_n_threads(optimal_threads());
+        params.set_language(Some("en"));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        params.set_suppress_blank(true);
+        state
+            .full(params, &dummy)
+            .map_err(|e| WhisperError::Transcription(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn transcribe(&self, samples: &[f32], options: &TranscribeOptions) -> Result<Transcript, WhisperError> {
+        if !audio::is_audio_usable(samples) {
+            return Ok(Transcript::default());
+        }
+
+        let mut state = self
+            .ctx
+            .create_state()
+            .map_err(|e| WhisperError::StateCreate(e.to_string()))?;
+
+        // Detect up front instead of letting whisper.cpp do it inside `full`:
+        // same cost, but the probability is kept and candidates can be limited
+        let decoding = &options.decoding;
+        let threads = decoding_threads(decoding);
+        let detected = if options.language == "auto" {
+            detect_language(&mut state, samples, options.allowed_languages, threads as usize)?
+        } else {
+            None
+        };
+        let language = detected.as_ref().map_or(options.language, |d| d.code.as_str());
+
+        let mut params = FullParams::new(sampling_strategy(decoding));
+        params.set_n_threads(threads);
+        params.set_language(Some(language));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        params.set_token_timestamps(options.word_timestamps);
+        params.set_translate(options.translate);
+
+        // Anti-hallucination settings
+        params.set_suppress_blank(true);
+        params.set_no_speech_thold(decoding.no_speech_threshold);
+        params.set_temperature_inc(decoding.temperature_inc); // 0 disables fallback (the "fast" default)
+        params.set_entropy_thold(decoding.entropy_threshold); // retry or reject uncertain/hallucinated segments
+        params.set_logprob_thold(decoding.logprob_threshold);
+
+        if !options.initial_prompt.is_empty() {
+            params.set_initial_prompt(options.initial_prompt);
+        }
+
+        state
+            .full(params, samples)
+            .map_err(|e| WhisperError::Transcription(e.to_string()))?;
+
+        let n_segments = state.full_n_segments();
+        let mut transcript = Transcript {
+            language: detected.clone(),
+            ..Transcript::default()
+        };
+        let mut total_token_prob = 0.0f64;
+        let mut total_tokens = 0usize;
+        let token_eot = self.ctx.token_eot();
+
+        for i in 0..n_segments {
+            let Some(segment) = state.get_segment(i) else {
+                continue;
+            };
+            let no_speech = segment.no_speech_probability();
+            let skipped = no_speech > decoding.no_speech_threshold;
+            if skipped {
+                log::info!("skipping segment {i} with high no_speech_prob ({no_speech:.3})");
+            }
+
+            // Token probabilities for confidence scoring, and timing for words
+            let mut segment_prob = 0.0f64;
+            let mut segment_tokens = 0usize;
+            let mut timed = Vec::new();
+            for t in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(t as std::ffi::c_int) else {
+                    continue;
+                };
+                segment_prob += token.token_probability() as f64;
+                segment_tokens += 1;
+                if options.word_timestamps && token.token_id() < token_eot {
+                    let data = token.token_data();
+                    timed.push(TimedToken {
+                        bytes: token.to_bytes().unwrap_or_default().to_vec(),
+                        t0: data.t0,
+                        t1: data.t1,
+                        prob: data.p,
+                    });
+                }
+            }
+            if !skipped {
+                total_token_prob += segment_prob;
+                total_tokens += segment_tokens;
+            }
+
+            let segment_text = segment
+                .to_str()
+                .map_err(|e: whisper_rs::WhisperError| {
+                    WhisperError::Transcription(e.to_string())
+                })?;
+            transcript.segments.push(Segment {
+                start_ms: centis_to_ms(segment.start_timestamp()),
+                end_ms: centis_to_ms(segment.end_timestamp()),
+                text: segment_text.to_string(),
+                avg_prob: if segment_tokens > 0 { (segment_prob / segment_tokens as f64) as f32 } else { 0.0 },
+                no_speech_prob: no_speech,
+                skipped,
+                words: group_words(&timed),
+            });
+        }
+
+        let trimmed = transcript.text();
+
+        // Confidence gate: reject if average token probability is too low
+        if total_tokens > 0 {
+            let avg_prob = total_token_prob / total_tokens as f64;
+            log::info!("transcription confidence: avg_token_prob={avg_prob:.4}, tokens={total_tokens}, text={trimmed:?}");
+            if avg_prob < decoding.confidence_threshold as f64 {
+                log::info!("rejected low-confidence transcription (avg_prob={avg_prob:.4})");
+                transcript.rejection = Some(Rejection::LowConfidence {
+                    avg_prob: avg_prob as f32,
+                });
+                return Ok(transcript);
+            }
+        }
+
+        // Filter known Whisper hallucination patterns (common when no speech is present)
+        let spoken = if options.translate {
+            Some("en")
+        } else {
+            Some(language).filter(|l| *l != "auto")
+        };
+        if let Some(rule) = options.hallucination_filter.check(&trimmed, spoken) {
+            log::info!("filtered hallucinated text {trimmed:?} by rule {rule}");
+            transcript.rejection = Some(Rejection::Hallucination { rule: rule.clone() });
+        }
+
+        Ok(transcript)
+    }
+}
+
+/// Run Whisper's language detection on the first 30s of `samples` and pick
+/// the most likely language among `allowed` (any language when empty).
+fn detect_language(
+    state: &mut WhisperState,
+    samples: &[f32],
+    allowed: &[String],
+    threads: usize,
+) -> Result<Option<DetectedLanguage>, WhisperError> {
+    state
+        .pcm_to_mel(samples, threads)
+        .map_err(|e| WhisperError::Transcription(e.to_string()))?;
+    let (_, probs) = state
+        .lang_detect(0, threads)
+        .map_err(|e| WhisperError::Transcription(e.to_string()))?;
+    let candidates = probs
+        .iter()
+        .enumerate()
+        .filter_map(|(id, p)| whisper_rs::get_lang_str(id as std::ffi::c_int).map(|code| (code, *p)));
+    let detected = pick_language(candidates, allowed);
+    if let Some(d) = &detected {
+        log::info!("detected language: {} (p={:.3})", d.code, d.probability);
+    }
+    Ok(detected)
+}
+
+fn pick_language<'a>(
+    candidates: impl Iterator<Item = (&'a str, f32)>,
+    allowed: &[String],
+) -> Option<DetectedLanguage> {
+    candidates
+        .filter(|(code, _)| allowed.is_empty() || allowed.iter().any(|a| a == code))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(code, probability)| DetectedLanguage {
+            code: code.to_string(),
+            probability,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_threads() {
+        let cases = vec![
+            (1, 1),
+            (2, 2),
+            (3, 3),
+            (4, 4),
+            (5, 3),
+            (6, 4),
+            (8, 6),
+            (10, 8),
+            (12, 8),
+            (16, 8),
+            (32, 8),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                calculate_threads(input),
+                expected,
+                "failed for input {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_sampling_strategy_from_decoding() {
+        let fast = Decoding::default();
+        assert!(matches!(sampling_strategy(&fast), SamplingStrategy::Greedy { best_of: 1 }));
+        let accurate = Decoding::preset("accurate").unwrap();
+        assert!(matches!(
+            sampling_strategy(&accurate),
+            SamplingStrategy::BeamSearch { beam_size: 5, .. }
+        ));
+        assert_eq!(decoding_threads(&Decoding { threads: 3, ..fast }), 3);
+        assert_eq!(decoding_threads(&fast), optimal_threads());
+    }
+
+    #[test]
+    fn test_pick_language_respects_allowed() {
+        let probs = [("en", 0.2), ("nn", 0.5), ("zh", 0.25), ("ja", 0.05)];
+        let any = pick_language(probs.into_iter(), &[]).unwrap();
+        assert_eq!(any.code, "nn");
+        let allowed = vec!["en".to_string(), "zh".to_string(), "ja".to_string()];
+        let picked = pick_language(probs.into_iter(), &allowed).unwrap();
+        assert_eq!(picked.code, "zh");
+        assert_eq!(picked.probability, 0.25);
+        assert!(pick_language(probs.into_iter(), &["ko".to_string()]).is_none());
+    }
+}
@@ -4,10 +4,13 @@
 // --- Event names ---
 pub const MODEL_DOWNLOAD_PROGRESS: &str = "model_download_progress";
 pub const MODEL_READY: &str = "model_ready";
+pub const MODEL_DOWNLOAD_CANCELLED: &str = "model_download_cancelled";
+pub const ENGINE_READY: &str = "engine_ready";
 pub const RECORDING_STATE_CHANGED: &str = "recording_state_changed";
 pub const PARTIAL_TRANSCRIPTION: &str = "partial_transcription";
 pub const TRANSCRIPTION_COMPLETE: &str = "transcription_complete";
 pub const TRANSCRIPTION_ENGINE_INFO: &str = "transcription_engine_info";
+pub const TRANSCRIPTION_REJECTED: &str = "transcription_rejected";
 pub const FOREGROUND_APP_INFO: &str = "foreground_app_info";
 pub const OPACITY_CHANGED: &str = "opacity_changed";
 pub const RECORDING_ERROR: &str = "recording_error";
@@ -16,6 +19,9 @@ pub const ACCESSIBILITY_GRANTED: &str = "accessibility_granted";
 pub const ENHANCER_INFO: &str = "enhancer_info";
 pub const RECORDING_CANCELLED: &str = "recording_cancelled";
 pub const RECORDING_MODE_INFO: &str = "recording_mode_info";
+pub const INPUT_DEVICE_CHANGED: &str = "input_device_changed";
+pub const AUDIO_LEVEL: &str = "audio_level";
+pub const HISTORY_CHANGED: &str = "history_changed";
 
 // --- Recording state strings ---
 pub const STATE_IDLE: &str = "idle";
@@ -27,9 +27,11 @@ impl HotkeySlot {
     }
 }
 
-/// 4 slots indexed by RecordingMode as usize:
-/// [0] = Dictation, [1] = Translate, [2] = VoiceCommand, [3] = ClipboardRewrite
-static HOTKEY_SLOTS: [HotkeySlot; 4] = [
+/// 5 slots indexed by RecordingMode as usize:
+/// [0] = Dictation, [1] = Translate, [2] = VoiceCommand, [3] = ClipboardRewrite,
+/// [4] = SpeechToEnglish
+static HOTKEY_SLOTS: [HotkeySlot; 5] = [
+    HotkeySlot::new(),
     HotkeySlot::new(),
     HotkeySlot::new(),
     HotkeySlot::new(),
@@ -112,16 +114,17 @@ static KEY_WAS_DOWN: AtomicBool = AtomicBool::new(false);
 static COMBO_ACTIVE: AtomicBool = AtomicBool::new(false);
 static COMBO_ACTIVE_SLOT: AtomicU32 = AtomicU32::new(u32::MAX);
 
-const MODES: [RecordingMode; 4] = [
+const MODES: [RecordingMode; 5] = [
     RecordingMode::Dictation,
     RecordingMode::Translate,
     RecordingMode::VoiceCommand,
     RecordingMode::ClipboardRewrite,
+    RecordingMode::SpeechToEnglish,
 ];
 
 /// Mode order for matching: combo-only modes first (more specific), modifier-only last.
-/// VoiceCommand(2), ClipboardRewrite(3), Translate(1), Dictation(0)
-const MATCH_ORDER: [usize; 4] = [2, 3, 1, 0];
+/// VoiceCommand(2), ClipboardRewrite(3), SpeechToEnglish(4), Translate(1), Dictation(0)
+const MATCH_ORDER: [usize; 5] = [2, 3, 4, 1, 0];
 
 /// Find which slot matches the given flags + keycode for a key-down event.
 /// Returns (slot_index, RecordingMode) if found.
@@ -33,9 +33,11 @@ impl HotkeySlot {
     }
 }
 
-/// 4 slots indexed by RecordingMode as usize:
-/// [0] = Dictation, [1] = Translate, [2] = VoiceCommand, [3] = ClipboardRewrite
-static HOTKEY_SLOTS: [HotkeySlot; 4] = [
+/// 5 slots indexed by RecordingMode as usize:
+/// [0] = Dictation, [1] = Translate, [2] = VoiceCommand, [3] = ClipboardRewrite,
+/// [4] = SpeechToEnglish
+static HOTKEY_SLOTS: [HotkeySlot; 5] = [
+    HotkeySlot::new(),
     HotkeySlot::new(),
     HotkeySlot::new(),
     HotkeySlot::new(),
@@ -66,15 +68,16 @@ pub(crate) fn pause_hotkey(mode: RecordingMode) {
     slot.regular_key.store(0, Ordering::SeqCst);
 }
 
-const MODES: [RecordingMode; 4] = [
+const MODES: [RecordingMode; 5] = [
     RecordingMode::Dictation,
     RecordingMode::Translate,
     RecordingMode::VoiceCommand,
     RecordingMode::ClipboardRewrite,
+    RecordingMode::SpeechToEnglish,
 ];
 
 /// Match order: combo-only modes first (more specific), modifier-only last.
-const MATCH_ORDER: [usize; 4] = [2, 3, 1, 0];
+const MATCH_ORDER: [usize; 5] = [2, 3, 4, 1, 0];
 
 /// Check if all packed modifier VK codes are currently held.
 fn all_modifiers_held(packed: u64) -> bool {
@@ -1,17 +1,22 @@
-mod audio;
 mod clipboard;
 mod events;
 mod frontapp;
 mod hotkey;
-mod llm;
-mod model;
 mod settings;
-mod state;
-mod whisper;
+
+use murmur_core::buffer::AudioSnapshot;
+use murmur_core::engine::SpeechEngine;
+use murmur_core::hallucination;
+use murmur_core::history::{self, History, HistoryEntry, HistoryError};
+use murmur_core::longform::LongFormTranscriber;
+use murmur_core::streaming::StreamingTranscriber;
+use murmur_core::transcript::{DetectedLanguage, Rejection, Transcript};
+use murmur_core::{audio, export, llm, model, pipeline, state, whisper};
+use settings::HotkeyTargets;
 
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Mutex;
+use std::sync::{mpsc, Mutex};
 use std::time::Instant;
 use tauri::{Emitter, Manager};
 
@@ -50,8 +55,11 @@ pub(crate) struct MurmurState {
     engine_init_done: (Mutex<bool>, std::sync::Condvar),
     settings: Mutex<settings::Settings>,
     live_stop: AtomicBool,
-    /// Join handle for the live transcription thread so stop_recording can wait for it.
-    live_thread: Mutex<Option<std::thread::JoinHandle<()>>>,
+    /// Join handle for the live transcription thread so stop_recording can
+    /// wait for it and reuse what it already transcribed.
+    live_thread: Mutex<Option<std::thread::JoinHandle<LiveTranscription>>>,
+    /// Cancels the pending auto-stop timer when dropped.
+    auto_stop: Mutex<Option<mpsc::Sender<()>>>,
     /// Generation counter for preview auto-hide timer cancellation.
     /// Incremented on each new recording; stale timers compare and bail out.
     preview_generation: AtomicU64,
@@ -61,12 +69,28 @@ pub(crate) struct MurmurState {
     manual_show: AtomicBool,
     /// Guard to prevent concurrent model downloads (main + onboarding can both trigger).
     downloading: AtomicBool,
+    /// Set by `cancel_model_download`; polled by the running download.
+    download_cancel: AtomicBool,
     /// Guard to prevent concurrent translation operations.
     translating: AtomicBool,
     /// Active recording mode for the current recording cycle.
     active_mode: Mutex<state::RecordingMode>,
     /// Captured context (selected text / clipboard) for VoiceCommand/ClipboardRewrite.
     captured_context: Mutex<Option<String>>,
+    /// Transcript and recording duration (ms) of the last result, for
+    /// `save_transcript`.
+    last_transcript: Mutex<Option<(Transcript, u64)>>,
+    /// Transcription history; `None` when turned off in settings or the
+    /// database couldn't be opened.
+    history: Mutex<Option<History>>,
+}
+
+/// What the live transcription thread hands back when the recording stops.
+enum LiveTranscription {
+    /// Live preview, with the segments it committed.
+    Preview(StreamingTranscriber),
+    /// Long-form recording, transcribed up to its last chunk.
+    LongForm(LongFormTranscriber),
 }
 
 /// Signal that engine initialization is complete (success or failure).
@@ -81,10 +105,45 @@ fn signal_engine_init_done(app: &tauri::AppHandle) {
     }
 }
 
-/// Spawn a background thread to load the Whisper engine.
-/// Used by startup, post-download, and engine-switch paths.
-fn spawn_engine_load(app: tauri::AppHandle, model_path: std::path::PathBuf, context: &'static str) {
+/// Catalog entry for the model selected in settings.
+fn active_model_config(state: &MurmurState) -> model::ModelConfig {
+    let id = state
+        .settings
+        .lock()
+        .map(|s| s.model.clone())
+        .unwrap_or_else(|_| model::DEFAULT_MODEL.to_string());
+    model::ModelConfig::for_model(&id)
+}
+
+/// `MODEL_DOWNLOAD_PROGRESS` payload: a core progress report tagged with
+/// the catalog id it belongs to.
+#[derive(Clone, serde::Serialize)]
+struct ModelProgressPayload<'a> {
+    model: &'a str,
+    #[serde(flatten)]
+    progress: model::LoadProgress,
+}
+
+fn emit_model_progress(app: &tauri::AppHandle, model: &str, progress: model::LoadProgress) {
+    let _ = app.emit(events::MODEL_DOWNLOAD_PROGRESS, ModelProgressPayload { model, progress });
+}
+
+/// Spawn a background thread to verify and load the Whisper engine.
+/// Used by startup, post-download, engine-switch and model-switch paths.
+/// Reports the verifying/loading/warming_up stages, then `ENGINE_READY`.
+fn spawn_engine_load(app: tauri::AppHandle, config: model::ModelConfig, context: &'static str) {
     std::thread::spawn(move || {
+        let ms = app.state::<MurmurState>();
+        let model_id = model::find_model_by_filename(&config.filename).map_or("", |m| m.id);
+        emit_model_progress(&app, model_id, model::LoadProgress::stage(model::LoadStage::Verifying));
+        // Cheap unless the file changed since it was last verified
+        if let Err(e) = model::verify_model(&ms.app_data_dir, &config) {
+            log::error!("model verification failed ({}): {}", context, e);
+            let _ = app.emit(events::RECORDING_ERROR, e.to_string());
+            signal_engine_init_done(&app);
+            return;
+        }
+        let model_path = model::model_path(&ms.app_data_dir, &config.filename);
         let model_path_str = match model_path.to_str() {
             Some(s) => s.to_string(),
             None => {
@@ -93,23 +152,94 @@ fn spawn_engine_load(app: tauri::AppHandle, model_path: std::path::PathBuf, cont
                 return;
             }
         };
-        match whisper::TranscriptionEngine::new(&model_path_str) {
+        let on_stage = |stage| emit_model_progress(&app, model_id, model::LoadProgress::stage(stage));
+        match whisper::TranscriptionEngine::with_progress(&model_path_str, on_stage) {
             Ok(engine) => {
-                let ms = app.state::<MurmurState>();
+                // The user may have switched models while this one was loading;
+                // the newer load signals init done
+                if active_model_config(&ms).filename != config.filename {
+                    log::info!("discarding engine for {:?} ({}), model was switched", model_path, context);
+                    return;
+                }
                 if let Ok(mut lock) = ms.engine.lock() {
                     *lock = Some(engine);
                 }
                 signal_engine_init_done(&app);
+                let _ = app.emit(events::ENGINE_READY, model_id);
                 log::info!("whisper engine loaded ({})", context);
             }
             Err(e) => {
                 log::error!("engine init failed ({}): {}", context, e);
+                let _ = app.emit(events::RECORDING_ERROR, e.to_string());
                 signal_engine_init_done(&app);
             }
         }
     });
 }
 
+/// Unload the current engine and load the active model in the background,
+/// if it is downloaded and the local engine is in use.
+fn reload_engine(app: &tauri::AppHandle, context: &'static str) {
+    let state = app.state::<MurmurState>();
+    if let Ok(mut lock) = state.engine.lock() {
+        if lock.take().is_some() {
+            log::info!("unloaded whisper engine ({})", context);
+        }
+    }
+
+    let is_local = state.settings.lock().map(|s| s.uses_local_engine()).unwrap_or(true);
+    let config = active_model_config(&state);
+    if !is_local || !model::is_model_ready(&state.app_data_dir, &config) {
+        return;
+    }
+    if let Ok(mut done) = state.engine_init_done.0.lock() {
+        *done = false; // Mark as pending — background thread will set true
+    }
+    spawn_engine_load(app.clone(), config, context);
+}
+
+/// Download catalog model `id` with progress events. Loads it afterwards if
+/// it is the active model. Concurrent calls return Ok immediately; the
+/// caller that lost the race still receives progress events. A cancelled
+/// download emits `MODEL_DOWNLOAD_CANCELLED` and returns Ok.
+async fn download_and_load_model(app: &tauri::AppHandle, id: &str, context: &'static str) -> Result<(), String> {
+    let state = app.state::<MurmurState>();
+    let info = model::find_model(id).ok_or_else(|| model::ModelError::UnknownModel(id.to_string()).to_string())?;
+
+    if state.downloading.swap(true, Ordering::Acquire) {
+        return Ok(());
+    }
+    state.download_cancel.store(false, Ordering::SeqCst);
+
+    let base = state.app_data_dir.clone();
+    let mirror = state.settings.lock().map(|s| s.model_mirror_url.clone()).unwrap_or_default();
+    let config = model::ModelConfig::from(info).with_mirror(&mirror);
+    let app_progress = app.clone();
+    let model_id = info.id;
+    let result = model::download_model(&base, &config, &state.download_cancel, move |progress| {
+        emit_model_progress(&app_progress, model_id, progress);
+    })
+    .await;
+    state.downloading.store(false, Ordering::Release);
+    match result {
+        Ok(()) => {}
+        Err(model::ModelError::Cancelled) => {
+            log::info!("model download cancelled ({})", info.id);
+            let _ = app.emit(events::MODEL_DOWNLOAD_CANCELLED, info.id);
+            return Ok(());
+        }
+        Err(e) => return Err(e.to_string()),
+    }
+
+    let _ = app.emit(events::MODEL_READY, info.id);
+
+    let is_active = state.settings.lock().map(|s| s.model == info.id).unwrap_or(false);
+    if is_active {
+        reload_engine(app, context);
+    }
+    Ok(())
+}
+
 #[cfg(target_os = "macos")]
 fn is_accessibility_trusted() -> bool {
     extern "C" {
@@ -176,10 +306,11 @@ fn reset_to_idle(state: &MurmurState, app: &tauri::AppHandle) {
 /// Stop live transcription thread and audio recorder, discarding all captured audio.
 /// Does NOT reset state or emit events — call `reset_to_idle` separately.
 fn cancel_active_recording(state: &MurmurState) {
+    disarm_auto_stop(state);
     state.live_stop.store(true, Ordering::SeqCst);
     if let Ok(mut lt) = state.live_thread.lock() {
-        if let Some(handle) = lt.take() {
-            let _ = handle.join();
+        if let Some(Ok(LiveTranscription::LongForm(long_form))) = lt.take().map(|h| h.join()) {
+            long_form.discard();
         }
     }
     if let Ok(mut rec) = state.recorder.lock() {
@@ -202,6 +333,7 @@ fn emit_mode_info(app: &tauri::AppHandle, mode: state::RecordingMode, llm_enable
         state::RecordingMode::VoiceCommand => "voice_command",
         state::RecordingMode::ClipboardRewrite => "clipboard_rewrite",
         state::RecordingMode::Translate => "translate",
+        state::RecordingMode::SpeechToEnglish => "speech_to_english",
     };
     let _ = app.emit(events::RECORDING_MODE_INFO, mode_str);
 }
@@ -297,6 +429,26 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
                 *ctx = Some(text);
             }
         }
+        state::RecordingMode::SpeechToEnglish => {
+            // Whisper translates locally; remote engines and models trained
+            // without the translate task can't
+            let (is_local, model_id) = state
+                .settings
+                .lock()
+                .map(|s| (s.uses_local_engine(), s.model.clone()))
+                .map_err(|e| format!("settings mutex poisoned: {e}"))?;
+            if !is_local {
+                return Err("Select the Local (Whisper) engine to use Speak to English".to_string());
+            }
+            if !model::can_translate(&model_id) {
+                return Err(
+                    "The selected model can't translate. Choose Small, Medium or another non-Turbo model".to_string(),
+                );
+            }
+            if let Ok(mut ctx) = state.captured_context.lock() {
+                *ctx = None;
+            }
+        }
         _ => {
             // Clear any stale context
             if let Ok(mut ctx) = state.captured_context.lock() {
@@ -306,8 +458,12 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
     }
 
     // If local engine and model not downloaded, trigger download instead of recording
-    let is_local = state.settings.lock().map(|s| s.engine != "groq").unwrap_or(true);
-    if is_local && !model::is_model_ready(&state.app_data_dir, &model::ModelConfig::default()) {
+    let (is_local, model_id) = state
+        .settings
+        .lock()
+        .map(|s| (s.uses_local_engine(), s.model.clone()))
+        .unwrap_or_else(|_| (true, model::DEFAULT_MODEL.to_string()));
+    if is_local && !model::is_model_ready(&state.app_data_dir, &model::ModelConfig::for_model(&model_id)) {
         log::info!("model not ready, triggering download");
         show_main_window(app);
         state.main_visible.store(true, Ordering::SeqCst);
@@ -315,7 +471,6 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
         let _ = app.emit(events::RECORDING_STATE_CHANGED, "downloading_model");
 
         let app_clone = app.clone();
-        let base = state.app_data_dir.clone();
         std::thread::spawn(move || {
             let rt = match tokio::runtime::Runtime::new() {
                 Ok(rt) => rt,
@@ -325,29 +480,11 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
                     return;
                 }
             };
-            let config = model::ModelConfig::default();
-            let app_progress = app_clone.clone();
-            let result = rt.block_on(model::download_model(&base, &config, move |downloaded, total| {
-                let _ = app_progress.emit(events::MODEL_DOWNLOAD_PROGRESS, serde_json::json!({
-                    "downloaded": downloaded,
-                    "total": total,
-                }));
-            }));
-            match result {
-                Ok(()) => {
-                    let _ = app_clone.emit(events::MODEL_READY, ());
-                    // Load engine in background
-                    let ms = app_clone.state::<MurmurState>();
-                    if let Ok(mut done) = ms.engine_init_done.0.lock() {
-                        *done = false;
-                    }
-                    let model_path = model::model_path(&base, &config.filename);
-                    spawn_engine_load(app_clone, model_path, "first-record download");
-                }
-                Err(e) => {
-                    log::error!("model download failed: {}", e);
-                    let _ = app_clone.emit(events::RECORDING_ERROR, e.to_string());
-                }
+            // Unknown ids fall back to the default model, like ModelConfig::for_model
+            let id = model::find_model(&model_id).map_or(model::DEFAULT_MODEL, |m| m.id);
+            if let Err(e) = rt.block_on(download_and_load_model(&app_clone, id, "first-record download")) {
+                log::error!("model download failed: {}", e);
+                let _ = app_clone.emit(events::RECORDING_ERROR, e);
             }
         });
         return Ok(());
@@ -372,60 +509,70 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
         .map_err(|e| e.to_string())?;
     let _ = app.emit(events::RECORDING_STATE_CHANGED, events::STATE_STARTING);
 
-    let mut recorder = audio::AudioRecorder::new();
-    if let Err(e) = recorder.start() {
-        reset_to_idle(&state, app);
-        let _ = app.emit(events::RECORDING_ERROR, e.to_string());
-        hide_main_window(app);
-        state.main_visible.store(false, Ordering::SeqCst);
-        hide_preview_window(app);
-        return Err(e.to_string());
-    }
-
-    let mut recorder_lock = state
+    let requested_device = state
+        .settings
+        .lock()
+        .map(|s| s.input_device.clone())
+        .unwrap_or_default();
+    // Reuse the stored recorder so a warm pre-roll stream carries over
+    let start_result = state
         .recorder
         .lock()
-        .map_err(|e| format!("recorder mutex poisoned: {e}"))?;
-    *recorder_lock = Some(recorder);
+        .map_err(|e| format!("recorder mutex poisoned: {e}"))?
+        .get_or_insert_with(audio::AudioRecorder::new)
+        .start(&requested_device, RecorderEvents { app: app.clone() });
+    let active_device = match start_result {
+        Ok(name) => name,
+        Err(e) => {
+            reset_to_idle(&state, app);
+            let _ = app.emit(events::RECORDING_ERROR, e.to_string());
+            hide_main_window(app);
+            state.main_visible.store(false, Ordering::SeqCst);
+            hide_preview_window(app);
+            return Err(e.to_string());
+        }
+    };
+    if !requested_device.is_empty() && active_device != requested_device {
+        let _ = app.emit(events::INPUT_DEVICE_CHANGED, serde_json::json!({
+            "requested": requested_device,
+            "active": active_device,
+        }));
+    }
 
     let _ = state
         .app_state
         .transition(state::RecordingState::Recording);
     let _ = app.emit(events::RECORDING_STATE_CHANGED, events::STATE_RECORDING);
 
-    // Auto-stop after 5 minutes in toggle mode
-    let rec_mode = state
+    // Toggle and long-form recordings stop themselves after their time limit
+    let (time_limit, long_form, is_local) = state
         .settings
         .lock()
-        .map(|s| s.recording_mode.clone())
-        .unwrap_or_default();
-    if rec_mode == "toggle" {
-        let app_timeout = app.clone();
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_secs(300));
-            let ms = app_timeout.state::<MurmurState>();
-            if ms.app_state.current() == state::RecordingState::Recording {
-                let _ = do_stop_recording(&app_timeout);
-            }
-        });
+        .map(|s| (s.recording_time_limit(mode), s.is_long_form(), s.uses_local_engine()))
+        .unwrap_or((None, false, true));
+    if let Some(limit) = time_limit {
+        arm_auto_stop(app, limit);
     }
 
-    // Start live transcription thread — only for Dictation mode with local engine.
+    // Start live transcription thread — only for Dictation mode. Long-form
+    // works with any engine; live preview needs the local one, as remote
+    // engines don't support streaming.
     // VoiceCommand/ClipboardRewrite record voice commands, live preview not useful.
-    let enable_live_preview = mode == state::RecordingMode::Dictation
-        && state
-            .settings
-            .lock()
-            .map(|s| s.engine != "groq")
-            .unwrap_or(true);
+    let enable_long_form = mode == state::RecordingMode::Dictation && long_form;
+    let enable_live_preview = mode == state::RecordingMode::Dictation && !long_form && is_local;
 
     state.live_stop.store(false, Ordering::SeqCst);
-    if enable_live_preview {
+    if enable_long_form {
+        let handle = spawn_long_form_thread(app);
+        if let Ok(mut lt) = state.live_thread.lock() {
+            *lt = Some(handle);
+        }
+    } else if enable_live_preview {
         let app_clone = app.clone();
         let handle = std::thread::spawn(move || {
             std::thread::sleep(std::time::Duration::from_millis(1500));
+            let mut stream = StreamingTranscriber::new();
             let mut last_text = String::new();
-            let mut locked_language: Option<String> = None;
 
             loop {
                 let ms = app_clone.state::<MurmurState>();
@@ -433,49 +580,31 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
                     break;
                 }
 
-                let samples = {
+                let recording = {
                     let lock = match ms.recorder.lock() {
                         Ok(l) => l,
                         Err(_) => break,
                     };
                     match lock.as_ref() {
-                        Some(rec) => rec.peek_samples().unwrap_or_else(|e| {
-                            log::warn!("failed to peek samples: {}", e);
-                            Vec::new()
+                        Some(rec) => rec.snapshot().unwrap_or_else(|e| {
+                            log::warn!("failed to snapshot recording: {}", e);
+                            AudioSnapshot::default()
                         }),
                         None => break,
                     }
                 };
 
                 // Need at least 2s of audio (32000 samples @ 16kHz) for stable live preview
-                if samples.len() < 32_000 {
+                if recording.len() < 32_000 {
                     std::thread::sleep(std::time::Duration::from_secs(1));
                     continue;
                 }
 
-                // Cap to last 10s (160k samples) to avoid repetition hallucinations
-                // on long recordings — Whisper degrades when re-transcribing long audio
-                let preview_samples = if samples.len() > 160_000 {
-                    &samples[samples.len() - 160_000..]
-                } else {
-                    &samples
-                };
-
-                let (language, initial_prompt) = ms
-                    .settings
-                    .lock()
-                    .map(|s| (s.whisper_language().to_string(), s.whisper_initial_prompt()))
-                    .unwrap_or_else(|_| ("auto".to_string(), String::new()));
-
-                // Lock language after first successful detection to prevent
-                // live preview flickering between languages on partial audio
-                let effective_language = if language == "auto" {
-                    locked_language.as_deref().unwrap_or("auto")
-                } else {
-                    &language
+                let settings = match ms.settings.lock() {
+                    Ok(s) => s.clone(),
+                    Err(_) => break,
                 };
-
-                let text = {
+                let transcript = {
                     let engine_lock = match ms.engine.try_lock() {
                         Ok(l) => l,
                         Err(_) => {
@@ -484,7 +613,15 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
                         }
                     };
                     match engine_lock.as_ref() {
-                        Some(engine) => engine.transcribe(preview_samples, effective_language, &initial_prompt).unwrap_or_default(),
+                        Some(engine) if !engine.supports_streaming() => break,
+                        // Only audio after the committed segments is decoded
+                        Some(engine) => pipeline::Pipeline::new(&settings)
+                            .with_engine(engine)
+                            .update_stream(&mut stream, &recording)
+                            .unwrap_or_else(|e| {
+                                log::warn!("live preview decode failed: {}", e);
+                                stream.preview()
+                            }),
                         None => {
                             // Engine not ready yet — wait and retry on next loop iteration
                             drop(engine_lock);
@@ -498,26 +635,15 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
                     break;
                 }
 
+                let text = transcript.text();
                 if !text.is_empty() && text != last_text {
                     last_text = text.clone();
                     let _ = app_clone.emit(events::PARTIAL_TRANSCRIPTION, &text);
-
-                    // Lock detected language for subsequent peeks to prevent flickering
-                    if language == "auto" && locked_language.is_none() {
-                        let has_cjk = text.chars().any(|c| {
-                            ('\u{4e00}'..='\u{9fff}').contains(&c)
-                                || ('\u{3040}'..='\u{30ff}').contains(&c)
-                                || ('\u{ac00}'..='\u{d7af}').contains(&c)
-                        });
-                        if has_cjk {
-                            locked_language = Some("zh".to_string());
-                            log::info!("live preview: locked language to zh based on CJK content");
-                        }
-                    }
                 }
 
                 std::thread::sleep(std::time::Duration::from_secs(2));
             }
+            LiveTranscription::Preview(stream)
         });
         if let Ok(mut lt) = state.live_thread.lock() {
             *lt = Some(handle);
@@ -527,17 +653,272 @@ fn do_start_recording(app: &tauri::AppHandle, mode: state::RecordingMode) -> Res
     Ok(())
 }
 
+/// Transcribes a long-form recording chunk by chunk while it runs, showing
+/// the text so far in the preview. Transcribed audio is released from the
+/// recorder, after being written to a temp file if the user keeps audio.
+fn spawn_long_form_thread(app: &tauri::AppHandle) -> std::thread::JoinHandle<LiveTranscription> {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let ms = app.state::<MurmurState>();
+        let mut long_form = LongFormTranscriber::new();
+        if ms.settings.lock().map(|s| s.long_form_keep_audio).unwrap_or(false) {
+            let started = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let path = std::env::temp_dir().join(format!("murmur-{started}.wav"));
+            if let Err(e) = long_form.retain_audio(&path) {
+                log::error!("failed to create {}: {}", path.display(), e);
+                let _ = app.emit(events::RECORDING_ERROR, format!("Recording won't be saved: {e}"));
+            }
+        }
+        let mut last_text = String::new();
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(2));
+            if ms.live_stop.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let recording = {
+                let lock = match ms.recorder.lock() {
+                    Ok(l) => l,
+                    Err(_) => break,
+                };
+                match lock.as_ref() {
+                    Some(rec) => match rec.snapshot() {
+                        Ok(snapshot) => snapshot,
+                        Err(e) => {
+                            log::warn!("failed to snapshot recording: {}", e);
+                            continue;
+                        }
+                    },
+                    None => break,
+                }
+            };
+            let settings = match ms.settings.lock() {
+                Ok(s) => s.clone(),
+                Err(_) => break,
+            };
+
+            let pipeline = pipeline::Pipeline::new(&settings);
+            let result = if pipeline.uses_remote_engine() {
+                pipeline.update_long_form(&mut long_form, &recording)
+            } else {
+                let engine_lock = match ms.engine.lock() {
+                    Ok(l) => l,
+                    Err(_) => break,
+                };
+                match engine_lock.as_ref() {
+                    Some(engine) => pipeline.with_engine(engine).update_long_form(&mut long_form, &recording),
+                    // Engine still loading; the audio waits in the recorder
+                    None => continue,
+                }
+            };
+            match result {
+                Ok(transcript) => {
+                    let text = transcript.text();
+                    if !text.is_empty() && text != last_text {
+                        last_text = text.clone();
+                        let _ = app.emit(events::PARTIAL_TRANSCRIPTION, &text);
+                    }
+                }
+                // The chunk is retried on the next pass
+                Err(e) => log::warn!("long-form transcription failed: {}", e),
+            }
+
+            if let Ok(lock) = ms.recorder.lock() {
+                if let Some(rec) = lock.as_ref() {
+                    rec.release(long_form.transcribed_samples());
+                }
+            }
+        }
+        LiveTranscription::LongForm(long_form)
+    })
+}
+
+/// Stops the recording after `limit`, unless it ends first. Replaces any
+/// earlier timer, so a timer never fires on a later recording.
+fn arm_auto_stop(app: &tauri::AppHandle, limit: std::time::Duration) {
+    let (cancel, cancelled) = mpsc::channel::<()>();
+    if let Ok(mut auto_stop) = app.state::<MurmurState>().auto_stop.lock() {
+        *auto_stop = Some(cancel);
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // Disarming drops the sender, which wakes this thread early
+        if cancelled.recv_timeout(limit) != Err(mpsc::RecvTimeoutError::Timeout) {
+            return;
+        }
+        let ms = app.state::<MurmurState>();
+        if ms.app_state.current() != state::RecordingState::Recording {
+            return;
+        }
+        log::info!("recording reached its {}s limit, stopping", limit.as_secs());
+        if let Err(e) = do_stop_recording(&app) {
+            log::error!("failed to stop recording: {}", e);
+            reset_to_idle(&ms, &app);
+            let _ = app.emit(events::RECORDING_ERROR, e);
+            hide_preview_window(&app);
+            hide_main_window(&app);
+        }
+    });
+}
+
+fn disarm_auto_stop(state: &MurmurState) {
+    if let Ok(mut auto_stop) = state.auto_stop.lock() {
+        auto_stop.take();
+    }
+}
+
+/// Transcribe `recording`, continuing from what the live thread already
+/// transcribed. Returns the transcript and the path of any audio kept by a
+/// long-form recording.
+fn finish_live_transcription(
+    pipeline: &pipeline::Pipeline,
+    live: Option<LiveTranscription>,
+    recording: &AudioSnapshot,
+    observer: &dyn pipeline::PipelineObserver,
+) -> Result<(Transcript, Option<PathBuf>), pipeline::PipelineError> {
+    match live {
+        Some(LiveTranscription::LongForm(long_form)) => pipeline.finish_long_form(long_form, recording, observer),
+        // Live preview already committed most of the recording
+        Some(LiveTranscription::Preview(stream)) if stream.has_committed() => {
+            Ok((pipeline.finish_stream(stream, recording, observer)?, None))
+        }
+        _ => Ok((pipeline.transcribe(&recording.to_vec(), observer)?, None)),
+    }
+}
+
+/// Forwards recorder events (input level, device loss) to the frontend.
+struct RecorderEvents {
+    app: tauri::AppHandle,
+}
+
+impl audio::RecorderObserver for RecorderEvents {
+    fn level(&mut self, level: audio::AudioLevel) {
+        let _ = self.app.emit(events::AUDIO_LEVEL, level);
+    }
+
+    fn device_lost(&mut self, err: audio::AudioError) {
+        // Runs on the recorder thread, which cancel_active_recording joins
+        let app = self.app.clone();
+        std::thread::spawn(move || handle_device_lost(&app, err));
+    }
+}
+
+/// The input device vanished mid-recording: drop the recording and tell the user.
+fn handle_device_lost(app: &tauri::AppHandle, err: audio::AudioError) {
+    let state = app.state::<MurmurState>();
+    if state.app_state.current() != state::RecordingState::Recording {
+        // A warm pre-roll stream died between recordings: reopen on the fallback device
+        sync_pre_roll(app);
+        return;
+    }
+
+    cancel_active_recording(&state);
+    reset_to_idle(&state, app);
+    let _ = app.emit(events::RECORDING_ERROR, err.to_string());
+    let _ = app.emit(events::INPUT_DEVICE_CHANGED, serde_json::json!({
+        "requested": state.settings.lock().map(|s| s.input_device.clone()).unwrap_or_default(),
+        "active": serde_json::Value::Null,
+    }));
+}
+
+/// Opens or closes the always-warm input stream to match the pre-roll setting.
+/// Does nothing mid-recording; the recorder keeps its stream until stopped.
+fn sync_pre_roll(app: &tauri::AppHandle) {
+    let state = app.state::<MurmurState>();
+    if state.app_state.current() != state::RecordingState::Idle {
+        return;
+    }
+    let Ok((enabled, device, pre_roll_ms)) = state
+        .settings
+        .lock()
+        .map(|s| (s.pre_roll_enabled, s.input_device.clone(), s.pre_roll_ms))
+    else {
+        return;
+    };
+    let Ok(mut recorder_lock) = state.recorder.lock() else {
+        return;
+    };
+    let recorder = recorder_lock.get_or_insert_with(audio::AudioRecorder::new);
+
+    if enabled {
+        if let Err(e) = recorder.warm(&device, pre_roll_ms, RecorderEvents { app: app.clone() }) {
+            log::warn!("failed to open input stream for pre-roll: {}", e);
+        }
+    } else if recorder.is_warm() {
+        recorder.shutdown();
+        log::info!("pre-roll disabled, input stream closed");
+    }
+}
+
+/// Forwards pipeline progress to the frontend as IPC events.
+struct AppObserver<'a> {
+    app: &'a tauri::AppHandle,
+    state: &'a MurmurState,
+}
+
+impl pipeline::PipelineObserver for AppObserver<'_> {
+    fn stage(&self, stage: pipeline::Stage) {
+        let (next, event_state) = match stage {
+            pipeline::Stage::Transcribing => (state::RecordingState::Transcribing, events::STATE_TRANSCRIBING),
+            pipeline::Stage::Processing => (state::RecordingState::Processing, events::STATE_PROCESSING),
+        };
+        let _ = self.state.app_state.transition(next);
+        let _ = self.app.emit(events::RECORDING_STATE_CHANGED, event_state);
+    }
+
+    fn engine_info(&self, name: &str, local: bool, language: Option<&DetectedLanguage>) {
+        let _ = self.app.emit(
+            events::TRANSCRIPTION_ENGINE_INFO,
+            serde_json::json!({
+                "engine": name,
+                "local": local,
+                "language": language.map(|l| &l.code),
+                "language_probability": language.map(|l| l.probability),
+            }),
+        );
+    }
+
+    fn enhancer_info(&self, name: &str, local: bool) {
+        let _ = self.app.emit(
+            events::ENHANCER_INFO,
+            serde_json::json!({
+                "name": name,
+                "local": local,
+            }),
+        );
+    }
+
+    fn rejected(&self, rejection: &Rejection) {
+        let _ = self.app.emit(
+            events::TRANSCRIPTION_REJECTED,
+            serde_json::json!({
+                "rejection": rejection,
+                "message": rejection.to_string(),
+            }),
+        );
+    }
+
+    fn warning(&self, message: &str) {
+        let _ = self.app.emit(events::RECORDING_ERROR, message);
+    }
+}
+
 fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
     let state = app.state::<MurmurState>();
 
+    disarm_auto_stop(&state);
     state.live_stop.store(true, Ordering::SeqCst);
 
     // Wait for the live transcription thread to finish so we don't block on engine lock
-    if let Ok(mut lt) = state.live_thread.lock() {
-        if let Some(handle) = lt.take() {
-            let _ = handle.join();
-        }
-    }
+    let live = state
+        .live_thread
+        .lock()
+        .ok()
+        .and_then(|mut lt| lt.take())
+        .and_then(|handle| handle.join().ok());
 
     state
         .app_state
@@ -545,18 +926,18 @@ fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
         .map_err(|e| e.to_string())?;
     let _ = app.emit(events::RECORDING_STATE_CHANGED, events::STATE_STOPPING);
 
-    let samples = {
+    let recording = {
         let mut recorder_lock = state
             .recorder
             .lock()
             .map_err(|e| format!("recorder mutex poisoned: {e}"))?;
         match recorder_lock.as_mut() {
             Some(recorder) => recorder.stop(),
-            None => Vec::new(),
+            None => AudioSnapshot::default(),
         }
     };
 
-    if samples.is_empty() {
+    if recording.is_empty() {
         reset_to_idle(&state, app);
         hide_main_window(app);
         state.main_visible.store(false, Ordering::SeqCst);
@@ -593,19 +974,22 @@ fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
         }
     }
 
-    let (engine_type, language, initial_prompt, api_key_for_whisper) = state
+    let settings = state
         .settings
         .lock()
-        .map(|s| (
-            s.engine.clone(),
-            s.whisper_language().to_string(),
-            s.whisper_initial_prompt(),
-            s.groq_api_key.clone(),
-        ))
-        .unwrap_or_else(|_| ("local".to_string(), "auto".to_string(), String::new(), String::new()));
-
-    // Anti-hallucination: skip if audio is too short or silent (applies to all engines)
-    if !audio::is_audio_usable(&samples) {
+        .map_err(|e| format!("settings mutex poisoned: {e}"))?
+        .clone();
+    let pipeline = pipeline::Pipeline::new(&settings)
+        .with_translation(active_mode == state::RecordingMode::SpeechToEnglish);
+    let observer = AppObserver { app, state: state.inner() };
+
+    // Anti-hallucination: skip if audio is too short or silent (applies to all engines).
+    // Long-form chunks already transcribed are no longer in `recording`.
+    let transcribed_live = matches!(&live, Some(LiveTranscription::LongForm(lf)) if lf.transcribed_samples() > 0);
+    if !transcribed_live && !recording.is_usable() {
+        if let Some(LiveTranscription::LongForm(long_form)) = live {
+            long_form.discard();
+        }
         reset_to_idle(&state, app);
         let _ = app.emit(
             events::TRANSCRIPTION_COMPLETE,
@@ -614,16 +998,9 @@ fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
         return Ok(String::new());
     }
 
-    let raw_text = if engine_type == "groq" && !api_key_for_whisper.is_empty() {
-        // Groq cloud Whisper
-        let rt = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
-        rt.block_on(llm::transcribe_groq(
-            &api_key_for_whisper,
-            &samples,
-            &language,
-            &initial_prompt,
-        ))
-        .map_err(|e| e.to_string())?
+    let (transcript, audio_path) = if pipeline.uses_remote_engine() {
+        // Groq or a custom OpenAI-compatible server
+        finish_live_transcription(&pipeline, live, &recording, &observer).map_err(|e| e.to_string())?
     } else {
         // Local Whisper — wait for background engine init if still running
         {
@@ -646,154 +1023,91 @@ fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
             .lock()
             .map_err(|e| format!("engine mutex poisoned: {e}"))?;
         match engine_lock.as_ref() {
-            Some(engine) => engine.transcribe(&samples, &language, &initial_prompt).map_err(|e| e.to_string())?,
+            Some(engine) => {
+                finish_live_transcription(&pipeline.with_engine(engine), live, &recording, &observer)
+                    .map_err(|e| e.to_string())?
+            }
             None => {
                 // Engine not available — retry init synchronously (task 4.4)
                 drop(engine_lock);
-                let model_path = model::model_path(&state.app_data_dir, &model::ModelConfig::default().filename);
+                let config = active_model_config(&state);
+                model::verify_model(&state.app_data_dir, &config).map_err(|e| e.to_string())?;
+                let model_path = model::model_path(&state.app_data_dir, &config.filename);
                 let model_path_str = model_path
                     .to_str()
                     .ok_or("model path contains invalid UTF-8")?;
                 log::info!("retrying engine init synchronously");
                 let engine = whisper::TranscriptionEngine::new(model_path_str)
                     .map_err(|e| format!("engine init retry failed: {e}"))?;
-                let text = engine
-                    .transcribe(&samples, &language, &initial_prompt)
+                let result = finish_live_transcription(&pipeline.with_engine(&engine), live, &recording, &observer)
                     .map_err(|e| e.to_string())?;
                 // Store engine for future use
                 if let Ok(mut lock) = state.engine.lock() {
                     *lock = Some(engine);
                 }
-                text
+                result
             }
         }
     };
-
-    let _ = app.emit(
-        events::TRANSCRIPTION_ENGINE_INFO,
-        serde_json::json!({
-            "engine": &engine_type,
-            "local": engine_type != "groq",
-        }),
-    );
-
-    log::debug!("[whisper raw] {}", raw_text);
-
-    // Branch based on active mode
-    let text = match active_mode {
-        state::RecordingMode::Dictation => {
-            // Existing dictation flow: text_replacement → optional LLM enhance → paste
-            let (enhancer, app_aware_style, raw_text) = {
-                let s = state
-                    .settings
-                    .lock()
-                    .map_err(|e| format!("settings mutex poisoned: {e}"))?;
-                (llm::create_enhancer(&s), s.app_aware_style, s.apply_replacements(&raw_text))
-            };
-
-            if let Some(enhancer) = enhancer {
-                if raw_text.is_empty() {
-                    raw_text
-                } else {
-                    let _ = state
-                        .app_state
-                        .transition(state::RecordingState::Processing);
-                    let _ = app.emit(events::RECORDING_STATE_CHANGED, events::STATE_PROCESSING);
-
-                    let _ = app.emit(
-                        events::ENHANCER_INFO,
-                        serde_json::json!({
-                            "name": enhancer.name(),
-                            "local": enhancer.is_local(),
-                        }),
-                    );
-
-                    let style = if app_aware_style {
-                        frontapp::foreground_app_bundle_id()
-                            .as_deref()
-                            .map(frontapp::style_for_app)
-                            .unwrap_or("default")
-                    } else {
-                        "default"
-                    };
-
-                    match enhancer.enhance(&raw_text, style) {
-                        Ok(processed) => {
-                            log::debug!("[llm output] {}", processed);
-                            processed
-                        }
-                        Err(e) => {
-                            log::error!("LLM post-processing failed: {}", e);
-                            let _ = app.emit(
-                                events::RECORDING_ERROR,
-                                format!("LLM processing failed, using raw text: {e}"),
-                            );
-                            raw_text
-                        }
-                    }
-                }
-            } else {
-                raw_text
-            }
-        }
-        state::RecordingMode::VoiceCommand | state::RecordingMode::ClipboardRewrite => {
-            // Voice command flow: skip text_replacement, require LLM
-            let context = state.captured_context.lock()
-                .ok()
-                .and_then(|mut ctx| ctx.take())
-                .unwrap_or_default();
-
-            if raw_text.trim().is_empty() {
-                reset_to_idle(&state, app);
-                return Ok(String::new());
-            }
-
-            let _ = state.app_state.transition(state::RecordingState::Processing);
-            let _ = app.emit(events::RECORDING_STATE_CHANGED, events::STATE_PROCESSING);
-
-            let enhancer = {
-                let s = state.settings.lock()
-                    .map_err(|e| format!("settings mutex poisoned: {e}"))?;
-                llm::create_enhancer(&s)
+    let raw_text = transcript.text();
+
+    // Branch based on active mode: replacements + optional LLM for dictation,
+    // LLM command execution for VoiceCommand/ClipboardRewrite
+    let style = if settings.app_aware_style {
+        frontapp::foreground_app_bundle_id()
+            .as_deref()
+            .map(frontapp::style_for_app)
+            .unwrap_or("default")
+    } else {
+        "default"
+    };
+    let request = pipeline::PipelineRequest {
+        mode: active_mode,
+        context: state
+            .captured_context
+            .lock()
+            .ok()
+            .and_then(|mut ctx| ctx.take())
+            .unwrap_or_default(),
+        style: style.to_string(),
+        language: pipeline.spoken_language(&transcript),
+    };
+    let (text, engine) = match pipeline.process(&raw_text, &request, &observer) {
+        Ok(result) => (result.text, result.engine),
+        Err(e) => {
+            let message = match &e {
+                pipeline::PipelineError::Llm(err) => format!("LLM processing failed: {err}"),
+                other => other.to_string(),
             };
-
-            match enhancer {
-                Some(enhancer) => {
-                    let _ = app.emit(
-                        events::ENHANCER_INFO,
-                        serde_json::json!({
-                            "name": enhancer.name(),
-                            "local": enhancer.is_local(),
-                        }),
-                    );
-
-                    let context_type = active_mode.context_type();
-                    match enhancer.execute_command(&raw_text, &context, context_type) {
-                        Ok(result) => {
-                            log::debug!("[llm command output] {}", result);
-                            result
-                        }
-                        Err(e) => {
-                            log::error!("LLM execute_command failed: {}", e);
-                            let _ = app.emit(events::RECORDING_ERROR, format!("LLM processing failed: {e}"));
-                            return Err(e.to_string());
-                        }
-                    }
-                }
-                None => {
-                    let err = "Enable AI Processing in Settings to use this mode";
-                    let _ = app.emit(events::RECORDING_ERROR, err);
-                    return Err(err.to_string());
-                }
-            }
-        }
-        state::RecordingMode::Translate => {
-            // Translate mode shouldn't go through the recording pipeline,
-            // but if it somehow does, just return the raw text.
-            raw_text
+            let _ = app.emit(events::RECORDING_ERROR, message);
+            return Err(e.to_string());
         }
     };
 
+    let duration_ms = audio::samples_to_ms(recording.len());
+    if !text.is_empty() {
+        record_history(
+            app,
+            &HistoryEntry {
+                id: 0,
+                created_at: history::now_ms(),
+                mode: active_mode,
+                engine,
+                app: frontapp::foreground_app_bundle_id().map(|id| match frontapp::display_name_for_app(&id) {
+                    "Unknown" => id,
+                    name => name.to_string(),
+                }),
+                raw_text: raw_text.clone(),
+                text: text.clone(),
+                duration_ms,
+                language: request.language.clone(),
+            },
+        );
+    }
+    if let Ok(mut last) = state.last_transcript.lock() {
+        *last = Some((transcript, duration_ms));
+    }
+
     // Detect if foreground app can accept paste (default: true, only false for Desktop/Finder)
     let has_input = if !text.is_empty() {
         std::panic::catch_unwind(frontapp::has_focused_text_input).unwrap_or(true)
@@ -833,7 +1147,12 @@ fn do_stop_recording(app: &tauri::AppHandle) -> Result<String, String> {
     reset_to_idle(&state, app);
     let _ = app.emit(
         events::TRANSCRIPTION_COMPLETE,
-        serde_json::json!({ "text": text, "mode": mode_str }),
+        serde_json::json!({
+            "text": text,
+            "mode": mode_str,
+            "language": request.language,
+            "audio_path": audio_path,
+        }),
     );
 
     // Show preview window now that result is ready
@@ -941,51 +1260,121 @@ fn get_recording_state(state: tauri::State<'_, MurmurState>) -> String {
 
 #[tauri::command]
 fn is_model_ready(state: tauri::State<'_, MurmurState>) -> bool {
-    model::is_model_ready(&state.app_data_dir, &model::ModelConfig::default())
+    model::is_model_ready(&state.app_data_dir, &active_model_config(&state))
 }
 
+/// Download a catalog model; defaults to the model selected in settings.
 #[tauri::command]
-async fn download_model_cmd(app: tauri::AppHandle) -> Result<(), String> {
-    let murmur_state = app.state::<MurmurState>();
+async fn download_model_cmd(app: tauri::AppHandle, model: Option<String>) -> Result<(), String> {
+    let id = match model {
+        Some(id) => id,
+        None => app
+            .state::<MurmurState>()
+            .settings
+            .lock()
+            .map_err(|e| format!("settings mutex poisoned: {e}"))?
+            .model
+            .clone(),
+    };
+    download_and_load_model(&app, &id, "post-download").await
+}
 
-    // Prevent concurrent downloads (main window + onboarding can both trigger).
-    // First caller wins; second caller silently returns Ok (it will receive progress events).
-    if murmur_state.downloading.swap(true, Ordering::Acquire) {
-        return Ok(());
+/// Stop the running model download. The partial file is kept so the next
+/// download resumes. Returns false if nothing was downloading.
+#[tauri::command]
+fn cancel_model_download(state: tauri::State<'_, MurmurState>) -> bool {
+    if !state.downloading.load(Ordering::Acquire) {
+        return false;
     }
+    state.download_cancel.store(true, Ordering::SeqCst);
+    true
+}
 
-    let base = murmur_state.app_data_dir.clone();
-
-    let app_clone = app.clone();
-    let result = model::download_model(&base, &model::ModelConfig::default(), move |downloaded, total| {
-        let _ = app_clone.emit(
-            events::MODEL_DOWNLOAD_PROGRESS,
-            serde_json::json!({
-                "downloaded": downloaded,
-                "total": total,
-            }),
-        );
+#[tauri::command]
+fn list_models(state: tauri::State<'_, MurmurState>) -> serde_json::Value {
+    let active = state.settings.lock().map(|s| s.model.clone()).unwrap_or_default();
+    serde_json::json!({
+        "models": model::list_models(&state.app_data_dir),
+        "active": active,
+        "disk_usage": model::disk_usage(&state.app_data_dir),
     })
-    .await;
-
-    result.map_err(|e| {
-        murmur_state.downloading.store(false, Ordering::Release);
-        e.to_string()
-    })?;
+}
 
-    let _ = app.emit(events::MODEL_READY, ());
-    murmur_state.downloading.store(false, Ordering::Release);
+/// Select a catalog model and reload the engine with it.
+#[tauri::command]
+fn switch_model(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, MurmurState>,
+    model: String,
+) -> Result<(), String> {
+    if model::find_model(&model).is_none() {
+        return Err(model::ModelError::UnknownModel(model).to_string());
+    }
+    let new_settings = {
+        let mut s = state.settings.lock().map_err(|e| format!("settings mutex poisoned: {e}"))?;
+        if s.model == model {
+            return Ok(());
+        }
+        s.model = model;
+        s.clone()
+    };
+    settings::save_settings(&new_settings, &state.app_data_dir)?;
+    reload_engine(&app, "model switch");
+    Ok(())
+}
 
-    // Spawn background engine init (don't block the command)
-    if let Ok(mut done) = murmur_state.engine_init_done.0.lock() {
-        *done = false; // Mark as pending — background thread will set true
+#[tauri::command]
+fn delete_model(state: tauri::State<'_, MurmurState>, model: String) -> Result<(), String> {
+    let active = state.settings.lock().map(|s| s.model.clone()).unwrap_or_default();
+    if model == active {
+        return Err("cannot delete the active model, switch to another model first".to_string());
     }
-    let model_path = model::model_path(&base, &model::ModelConfig::default().filename);
-    spawn_engine_load(app.clone(), model_path, "post-download");
+    model::delete_model(&state.app_data_dir, &model).map_err(|e| e.to_string())
+}
+
+/// Install a local ggml file as a catalog model, for offline machines.
+/// `model` defaults to the catalog entry with the file's name. With `link`
+/// the file is symlinked instead of copied.
+#[tauri::command]
+async fn import_model(
+    app: tauri::AppHandle,
+    path: String,
+    model: Option<String>,
+    link: bool,
+) -> Result<(), String> {
+    let source = std::path::PathBuf::from(path);
+    let id = match model {
+        Some(id) => id,
+        None => {
+            let filename = source.file_name().and_then(|f| f.to_str()).unwrap_or_default();
+            model::find_model_by_filename(filename)
+                .map(|m| m.id.to_string())
+                .ok_or_else(|| format!("{filename} is not a known model file, choose the model to import it as"))?
+        }
+    };
+
+    let base = app.state::<MurmurState>().app_data_dir.clone();
+    let import_id = id.clone();
+    tokio::task::spawn_blocking(move || model::import_model(&base, &import_id, &source, link))
+        .await
+        .map_err(|e| format!("import task failed: {e}"))?
+        .map_err(|e| e.to_string())?;
+    log::info!("imported model {}", id);
 
+    let state = app.state::<MurmurState>();
+    let _ = app.emit(events::MODEL_READY, &id);
+    let is_active = state.settings.lock().map(|s| s.model == id).unwrap_or(false);
+    if is_active {
+        reload_engine(&app, "model import");
+    }
     Ok(())
 }
 
+#[tauri::command]
+fn list_input_devices() -> Vec<audio::InputDevice> {
+    audio::list_input_devices()
+}
+
 #[tauri::command]
 fn start_recording(app: tauri::AppHandle) -> Result<(), String> {
     do_start_recording(&app, state::RecordingMode::Dictation)
@@ -996,6 +1385,13 @@ fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
     do_stop_recording(&app)
 }
 
+/// Built-in hallucination filter rules, listed in settings so users can
+/// turn individual ones off.
+#[tauri::command]
+fn get_hallucination_rules() -> Vec<hallucination::FilterRule> {
+    hallucination::builtin_rules()
+}
+
 #[tauri::command]
 fn get_settings(state: tauri::State<'_, MurmurState>) -> settings::Settings {
     state
@@ -1011,6 +1407,8 @@ fn save_settings(
     state: tauri::State<'_, MurmurState>,
     app: tauri::AppHandle,
 ) -> Result<(), String> {
+    new_settings.validate()?;
+
     // Apply all hotkey changes
     let dict_t = new_settings.ptt_key_target();
     hotkey::set_hotkey(state::RecordingMode::Dictation, dict_t.modifier_mask, dict_t.regular_key);
@@ -1025,6 +1423,9 @@ fn save_settings(
     let cr = new_settings.clipboard_rewrite_key_target();
     hotkey::set_hotkey(state::RecordingMode::ClipboardRewrite, cr.modifier_mask, cr.regular_key);
 
+    let se = new_settings.speech_to_english_key_target();
+    hotkey::set_hotkey(state::RecordingMode::SpeechToEnglish, se.modifier_mask, se.regular_key);
+
     // Apply window opacity
     if let Some(window) = app.get_webview_window("main") {
         let _ = app.emit(events::OPACITY_CHANGED, new_settings.window_opacity);
@@ -1034,37 +1435,35 @@ fn save_settings(
     // Persist first, then update in-memory state in a single lock
     settings::save_settings(&new_settings, &state.app_data_dir)?;
 
-    let engine_changed = {
+    let (engine_changed, is_local_engine, model_changed) = {
         let mut s = state.settings.lock().map_err(|e| format!("settings mutex poisoned: {e}"))?;
-        let changed = s.engine != new_settings.engine;
+        let is_local = new_settings.uses_local_engine();
+        let changed = s.uses_local_engine() != is_local;
+        let model_changed = s.model != new_settings.model;
         *s = new_settings;
-        changed
+        (changed, is_local, model_changed)
     };
 
+    // Pre-roll or input device may have changed
+    sync_pre_roll(&app);
+
     // Handle engine lifecycle on switch
-    if engine_changed {
-        let new_engine = &state.settings.lock().map_err(|e| format!("settings mutex poisoned: {e}"))?.engine.clone();
-        if new_engine == "groq" {
-            // Unload whisper engine (frees ~2GB of memory)
-            if let Ok(mut lock) = state.engine.lock() {
-                if lock.take().is_some() {
-                    log::info!("unloaded whisper engine (switched to groq)");
-                }
-            }
-        } else {
-            // Load whisper engine in background
-            let model_config = model::ModelConfig::default();
-            if model::is_model_ready(&state.app_data_dir, &model_config) {
-                if let Ok(mut done) = state.engine_init_done.0.lock() {
-                    *done = false;
-                }
-                let model_path = model::model_path(&state.app_data_dir, &model_config.filename);
-                spawn_engine_load(app.clone(), model_path, "engine switch");
+    if engine_changed && !is_local_engine {
+        // Unload whisper engine (frees ~2GB of memory)
+        if let Ok(mut lock) = state.engine.lock() {
+            if lock.take().is_some() {
+                log::info!("unloaded whisper engine (switched to remote engine)");
             }
         }
+    } else if engine_changed {
+        reload_engine(&app, "engine switch");
+    } else if model_changed && is_local_engine {
+        reload_engine(&app, "model switch");
     }
 
-    Ok(())
+    // Last, so a history location that can't be used doesn't stop the rest
+    // from applying; the error still reaches Settings
+    sync_history(&state)
 }
 
 #[tauri::command]
@@ -1100,8 +1499,30 @@ fn copy_to_clipboard(text: String) -> Result<(), String> {
     clipboard::copy_only(&text).map_err(|e| e.to_string())
 }
 
+/// Write the last result to `path` as subtitles, JSON or Markdown, chosen by
+/// the file extension. `text` is what the preview shows; segment timing is
+/// kept only if it wasn't rewritten or edited.
 #[tauri::command]
-fn translate_text(text: String, app: tauri::AppHandle) -> Result<String, String> {
+fn save_transcript(path: PathBuf, text: String, app: tauri::AppHandle) -> Result<(), String> {
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(export::ExportFormat::from_extension)
+        .ok_or("Save as .srt, .vtt, .json or .md")?;
+    let state = app.state::<MurmurState>();
+    let last = state
+        .last_transcript
+        .lock()
+        .map_err(|e| format!("transcript mutex poisoned: {e}"))?;
+    let (transcript, duration_ms) = last.as_ref().ok_or("No transcript to save")?;
+    let output = export::render(&export::for_text(transcript, text.trim(), *duration_ms), format);
+    std::fs::write(&path, output).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+/// `language` is the spoken language of a dictation result, if known; it
+/// decides the translation direction better than the text alone.
+#[tauri::command]
+fn translate_text(text: String, language: Option<String>, app: tauri::AppHandle) -> Result<String, String> {
     let state = app.state::<MurmurState>();
     let settings = state
         .settings
@@ -1110,7 +1531,7 @@ fn translate_text(text: String, app: tauri::AppHandle) -> Result<String, String>
         .clone();
     let translator = llm::create_translator(&settings)
         .ok_or("Enable AI Processing provider in Settings to use translation")?;
-    let target = llm::detect_target_language(&text);
+    let target = llm::translation_target(language.as_deref(), &text);
     let translated = translator.translate(&text, target).map_err(|e| e.to_string())?;
     clipboard::copy_only(&translated).map_err(|e| e.to_string())?;
     Ok(translated)
@@ -1232,6 +1653,131 @@ fn add_dictionary_terms(
     Ok(())
 }
 
+// --- History ---
+
+/// Open, move or close the history database to match settings, then apply
+/// the retention limit.
+fn sync_history(state: &MurmurState) -> Result<(), String> {
+    let (enabled, path, retention) = {
+        let s = state.settings.lock().map_err(|e| format!("settings mutex poisoned: {e}"))?;
+        (s.history_enabled, s.history_path(&state.app_data_dir), s.history_retention())
+    };
+    let mut lock = state.history.lock().map_err(|e| format!("history mutex poisoned: {e}"))?;
+    if !enabled {
+        // The database stays on disk for when history is turned back on
+        *lock = None;
+        return Ok(());
+    }
+    // A failed move leaves the open database in place and recording
+    match lock.as_mut() {
+        Some(history) => history.relocate(&path).map_err(|e| e.to_string())?,
+        None => *lock = Some(History::open(&path).map_err(|e| e.to_string())?),
+    }
+    let Some(history) = lock.as_ref() else {
+        return Ok(());
+    };
+    log::info!("history database: {}", history.path().display());
+    if let Some(max_age) = retention {
+        history.prune(max_age, history::now_ms()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Record a result, if history is on. Failures are logged, never shown:
+/// the dictation itself succeeded.
+fn record_history(app: &tauri::AppHandle, entry: &HistoryEntry) {
+    let state = app.state::<MurmurState>();
+    let retention = state.settings.lock().ok().and_then(|s| s.history_retention());
+    let Ok(lock) = state.history.lock() else {
+        return;
+    };
+    let Some(history) = lock.as_ref() else {
+        return;
+    };
+    if let Err(e) = history.add(entry) {
+        log::error!("failed to record history entry: {e}");
+        return;
+    }
+    if let Some(max_age) = retention {
+        if let Err(e) = history.prune(max_age, entry.created_at) {
+            log::warn!("failed to prune history: {e}");
+        }
+    }
+    let _ = app.emit(events::HISTORY_CHANGED, ());
+}
+
+fn with_history<T>(
+    state: &MurmurState,
+    f: impl FnOnce(&History) -> Result<T, HistoryError>,
+) -> Result<T, String> {
+    let lock = state.history.lock().map_err(|e| format!("history mutex poisoned: {e}"))?;
+    let history = lock.as_ref().ok_or("History is turned off in Settings")?;
+    f(history).map_err(|e| e.to_string())
+}
+
+fn history_text(state: &MurmurState, id: i64) -> Result<String, String> {
+    with_history(state, |h| h.get(id))?
+        .map(|entry| entry.text)
+        .ok_or_else(|| "History entry not found".to_string())
+}
+
+/// Newest entries first, `limit` at a time.
+#[tauri::command]
+fn list_history(offset: usize, limit: usize, state: tauri::State<'_, MurmurState>) -> Result<Vec<HistoryEntry>, String> {
+    with_history(&state, |h| h.list(offset, limit))
+}
+
+#[tauri::command]
+fn search_history(query: String, limit: usize, state: tauri::State<'_, MurmurState>) -> Result<Vec<HistoryEntry>, String> {
+    with_history(&state, |h| h.search(&query, limit))
+}
+
+#[tauri::command]
+fn copy_history_entry(id: i64, state: tauri::State<'_, MurmurState>) -> Result<(), String> {
+    clipboard::copy_only(&history_text(&state, id)?).map_err(|e| e.to_string())
+}
+
+/// Paste an entry's text into the app that was in front before the history
+/// window: hide the window, give focus a moment to return, then paste.
+#[tauri::command]
+fn paste_history_entry(
+    id: i64,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, MurmurState>,
+) -> Result<(), String> {
+    let text = history_text(&state, id)?;
+    window.hide().map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        if let Err(e) = clipboard::insert_text(&text) {
+            log::error!("failed to paste history entry: {e}");
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_history_entry(id: i64, state: tauri::State<'_, MurmurState>) -> Result<(), String> {
+    with_history(&state, |h| h.delete(id)).map(|_| ())
+}
+
+#[tauri::command]
+fn open_history(app: tauri::AppHandle) {
+    if let Some(w) = app.get_webview_window("history") {
+        let _ = w.show();
+        let _ = w.set_focus();
+    } else {
+        let _ = tauri::WebviewWindowBuilder::new(
+            &app,
+            "history",
+            tauri::WebviewUrl::App("history.html".into()),
+        )
+        .title("Murmur Voice History")
+        .inner_size(520.0, 640.0)
+        .build();
+    }
+}
+
 #[tauri::command]
 fn open_settings(app: tauri::AppHandle) {
     if let Some(w) = app.get_webview_window("settings") {
@@ -1257,7 +1803,8 @@ pub fn run() {
 
     #[allow(unused_mut)]
     let mut builder = tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init());
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init());
 
     #[cfg(target_os = "macos")]
     {
@@ -1269,15 +1816,29 @@ pub fn run() {
             get_recording_state,
             is_model_ready,
             download_model_cmd,
+            cancel_model_download,
+            list_models,
+            switch_model,
+            delete_model,
+            import_model,
+            list_input_devices,
             start_recording,
             stop_recording,
             get_settings,
+            get_hallucination_rules,
             save_settings,
             open_settings,
             hide_preview,
             hide_overlay_windows,
             complete_onboarding,
             copy_to_clipboard,
+            save_transcript,
+            list_history,
+            search_history,
+            copy_history_entry,
+            paste_history_entry,
+            delete_history_entry,
+            open_history,
             translate_text,
             pause_hotkey_listener,
             resume_hotkey_listener,
@@ -1314,12 +1875,15 @@ pub fn run() {
             hotkey::set_hotkey(state::RecordingMode::VoiceCommand, vc.modifier_mask, vc.regular_key);
             let cr = initial_settings.clipboard_rewrite_key_target();
             hotkey::set_hotkey(state::RecordingMode::ClipboardRewrite, cr.modifier_mask, cr.regular_key);
+            let se = initial_settings.speech_to_english_key_target();
+            hotkey::set_hotkey(state::RecordingMode::SpeechToEnglish, se.modifier_mask, se.regular_key);
 
             // Register MurmurState with the resolved app_data_dir.
             // engine_init_done starts as `false` only when background engine init will run
             // (local engine + model ready). Otherwise `true` (nothing to wait for).
-            let model_ready = model::is_model_ready(&app_data_dir, &model::ModelConfig::default());
-            let is_local_engine = initial_settings.engine != "groq";
+            let model_config = model::ModelConfig::for_model(&initial_settings.model);
+            let model_ready = model::is_model_ready(&app_data_dir, &model_config);
+            let is_local_engine = initial_settings.uses_local_engine();
             let will_load_engine = model_ready && is_local_engine;
             app.manage(MurmurState {
                 app_data_dir: app_data_dir.clone(),
@@ -1330,23 +1894,32 @@ pub fn run() {
                 settings: Mutex::new(initial_settings),
                 live_stop: AtomicBool::new(false),
                 live_thread: Mutex::new(None),
+                auto_stop: Mutex::new(None),
                 preview_generation: AtomicU64::new(0),
                 main_visible: AtomicBool::new(false),
                 manual_show: AtomicBool::new(false),
                 downloading: AtomicBool::new(false),
+                download_cancel: AtomicBool::new(false),
                 translating: AtomicBool::new(false),
                 active_mode: Mutex::new(state::RecordingMode::Dictation),
                 captured_context: Mutex::new(None),
+                last_transcript: Mutex::new(None),
+                history: Mutex::new(None),
             });
+            if let Err(e) = sync_history(&app.state::<MurmurState>()) {
+                log::error!("failed to open history: {e}");
+            }
 
-            // Create system tray with Settings + Show/Hide + Quit
+            // Create system tray with Settings + History + Show/Hide + Quit
             let settings_item =
                 tauri::menu::MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
+            let history_item =
+                tauri::menu::MenuItem::with_id(app, "history", "History...", true, None::<&str>)?;
             let show_item =
                 tauri::menu::MenuItem::with_id(app, "show_toggle", "Show", true, None::<&str>)?;
             let quit =
                 tauri::menu::MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = tauri::menu::Menu::with_items(app, &[&settings_item, &show_item, &quit])?;
+            let menu = tauri::menu::Menu::with_items(app, &[&settings_item, &history_item, &show_item, &quit])?;
             let show_item_ref = show_item.clone();
             let _tray = tauri::tray::TrayIconBuilder::new()
                 .icon(app.default_window_icon().cloned().unwrap())
@@ -1355,6 +1928,7 @@ pub fn run() {
                 .on_menu_event(move |app, event| match event.id().as_ref() {
                     "quit" => app.exit(0),
                     "settings" => open_settings(app.clone()),
+                    "history" => open_history(app.clone()),
                     "show_toggle" => {
                         let ms = app.state::<MurmurState>();
                         let visible = ms.main_visible.load(Ordering::SeqCst);
@@ -1381,6 +1955,11 @@ pub fn run() {
                 s.settings.lock().map(|s| !s.onboarding_complete).unwrap_or(false)
             };
 
+            // Opt-in pre-roll keeps the microphone open from launch
+            if !needs_onboarding {
+                sync_pre_roll(app.handle());
+            }
+
             if needs_onboarding {
                 if let Some(w) = app.get_webview_window("main") {
                     let _ = w.hide();
@@ -1453,8 +2032,7 @@ pub fn run() {
             // Load whisper engine in background thread only for local engine users.
             // Groq users don't need the local model at all — saves ~2GB of memory.
             if will_load_engine {
-                let model_path = model::model_path(&app_data_dir, &model::ModelConfig::default().filename);
-                spawn_engine_load(app.handle().clone(), model_path, "startup");
+                spawn_engine_load(app.handle().clone(), model_config, "startup");
             }
 
             // Start hotkey listener
@@ -1533,7 +2111,7 @@ pub fn run() {
                                 continue;
                             }
 
-                            // Recording modes (Dictation, VoiceCommand, ClipboardRewrite)
+                            // Recording modes (Dictation, VoiceCommand, ClipboardRewrite, SpeechToEnglish)
                             let rec_mode = {
                                 let ms = app_handle.state::<MurmurState>();
                                 ms.settings
@@ -1543,7 +2121,7 @@ pub fn run() {
                             };
 
                             match rec_mode.as_str() {
-                                "toggle" => {
+                                "toggle" | "long_form" => {
                                     // Debounce: skip if last toggle was < 500ms ago
                                     if let Some(last) = last_toggle {
                                         if last.elapsed()
@@ -1614,7 +2192,7 @@ pub fn run() {
                                     .map(|s| s.recording_mode.clone())
                                     .unwrap_or_else(|_| "hold".to_string())
                             };
-                            if rec_mode == "toggle" || !is_recording {
+                            if matches!(rec_mode.as_str(), "toggle" | "long_form") || !is_recording {
                                 continue;
                             }
                             is_recording = false;
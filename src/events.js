@@ -1,6 +1,8 @@
 const EVENTS = {
   MODEL_DOWNLOAD_PROGRESS: "model_download_progress",
   MODEL_READY: "model_ready",
+  MODEL_DOWNLOAD_CANCELLED: "model_download_cancelled",
+  ENGINE_READY: "engine_ready",
   RECORDING_STATE_CHANGED: "recording_state_changed",
   PARTIAL_TRANSCRIPTION: "partial_transcription",
   TRANSCRIPTION_COMPLETE: "transcription_complete",
@@ -12,7 +14,11 @@ const EVENTS = {
   ENHANCER_INFO: "enhancer_info",
   RECORDING_CANCELLED: "recording_cancelled",
   TRANSCRIPTION_ENGINE_INFO: "transcription_engine_info",
+  TRANSCRIPTION_REJECTED: "transcription_rejected",
   RECORDING_MODE_INFO: "recording_mode_info",
+  INPUT_DEVICE_CHANGED: "input_device_changed",
+  AUDIO_LEVEL: "audio_level",
+  HISTORY_CHANGED: "history_changed",
 };
 
 const RECORDING_STATES = {
@@ -30,14 +36,21 @@ const TRANSCRIPTION_MODES = {
   DICTATED: "dictated",
   VOICE_COMMAND: "voice_command",
   CLIPBOARD_REWRITE: "clipboard_rewrite",
+  SPEECH_TO_ENGLISH: "speech_to_english",
 };
 
 const COMMANDS = {
   GET_SETTINGS: "get_settings",
+  GET_HALLUCINATION_RULES: "get_hallucination_rules",
   SAVE_SETTINGS: "save_settings",
   OPEN_SETTINGS: "open_settings",
   IS_MODEL_READY: "is_model_ready",
   DOWNLOAD_MODEL_CMD: "download_model_cmd",
+  CANCEL_MODEL_DOWNLOAD: "cancel_model_download",
+  LIST_MODELS: "list_models",
+  SWITCH_MODEL: "switch_model",
+  DELETE_MODEL: "delete_model",
+  IMPORT_MODEL: "import_model",
   CHECK_ACCESSIBILITY: "check_accessibility",
   CHECK_MICROPHONE: "check_microphone",
   REQUEST_MICROPHONE: "request_microphone",
@@ -48,6 +61,13 @@ const COMMANDS = {
   ADD_DICTIONARY_TERM: "add_dictionary_term",
   ADD_DICTIONARY_TERMS: "add_dictionary_terms",
   COPY_TO_CLIPBOARD: "copy_to_clipboard",
+  SAVE_TRANSCRIPT: "save_transcript",
+  LIST_HISTORY: "list_history",
+  SEARCH_HISTORY: "search_history",
+  COPY_HISTORY_ENTRY: "copy_history_entry",
+  PASTE_HISTORY_ENTRY: "paste_history_entry",
+  DELETE_HISTORY_ENTRY: "delete_history_entry",
+  OPEN_HISTORY: "open_history",
   HIDE_PREVIEW: "hide_preview",
   HIDE_OVERLAY_WINDOWS: "hide_overlay_windows",
   PAUSE_TRANSLATE_HOTKEY: "pause_translate_hotkey",
@@ -55,4 +75,5 @@ const COMMANDS = {
   COMPLETE_ONBOARDING: "complete_onboarding",
   TRANSLATE_TEXT: "translate_text",
   RESIZE_MAIN_WINDOW: "resize_main_window",
+  LIST_INPUT_DEVICES: "list_input_devices",
 };
@@ -0,0 +1,146 @@
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+
+const PAGE_SIZE = 50;
+const SEARCH_LIMIT = 200;
+
+const el = (id) => document.getElementById(id);
+
+let entries = [];
+let query = "";
+let hasMore = false;
+let searchTimer = null;
+
+function formatDuration(ms) {
+  const secs = Math.round(ms / 1000);
+  if (secs < 60) return `${secs}s`;
+  return `${Math.floor(secs / 60)}:${String(secs % 60).padStart(2, "0")}`;
+}
+
+function button(label, className, onClick) {
+  const btn = document.createElement("button");
+  btn.className = className;
+  btn.textContent = label;
+  btn.addEventListener("click", onClick);
+  return btn;
+}
+
+function renderEntry(entry) {
+  const item = document.createElement("div");
+  item.className = "entry";
+
+  const meta = document.createElement("div");
+  meta.className = "entry-meta";
+  meta.textContent = [
+    new Date(entry.created_at).toLocaleString(currentLocale),
+    t(`history.mode.${entry.mode}`),
+    entry.app,
+    formatDuration(entry.duration_ms),
+    entry.language,
+    entry.engine,
+  ]
+    .filter(Boolean)
+    .join(" · ");
+  item.appendChild(meta);
+
+  const text = document.createElement("p");
+  text.className = "entry-text";
+  text.textContent = entry.text;
+  item.appendChild(text);
+
+  if (entry.raw_text && entry.raw_text !== entry.text) {
+    const raw = document.createElement("details");
+    raw.className = "entry-raw";
+    const summary = document.createElement("summary");
+    summary.textContent = t("history.raw");
+    const rawText = document.createElement("p");
+    rawText.textContent = entry.raw_text;
+    raw.append(summary, rawText);
+    item.appendChild(raw);
+  }
+
+  const actions = document.createElement("div");
+  actions.className = "entry-actions";
+  const copy = button(t("history.copy"), "update-btn", async () => {
+    try {
+      await invoke(COMMANDS.COPY_HISTORY_ENTRY, { id: entry.id });
+      copy.textContent = t("history.copied");
+      setTimeout(() => (copy.textContent = t("history.copy")), 1500);
+    } catch (e) {
+      showMessage(String(e));
+    }
+  });
+  const paste = button(t("history.paste"), "update-btn", () => {
+    invoke(COMMANDS.PASTE_HISTORY_ENTRY, { id: entry.id }).catch((e) => showMessage(String(e)));
+  });
+  const remove = button(t("history.delete"), "update-btn delete-btn", async () => {
+    try {
+      await invoke(COMMANDS.DELETE_HISTORY_ENTRY, { id: entry.id });
+      entries = entries.filter((e) => e.id !== entry.id);
+      item.remove();
+      if (entries.length === 0) render();
+    } catch (e) {
+      showMessage(String(e));
+    }
+  });
+  actions.append(copy, paste, remove);
+  item.appendChild(actions);
+  return item;
+}
+
+function render() {
+  const list = el("history-list");
+  list.replaceChildren(...entries.map(renderEntry));
+  el("history-more").classList.toggle("hidden", !hasMore);
+  if (entries.length === 0) {
+    showMessage(t(query ? "history.noResults" : "history.empty"));
+  } else {
+    el("history-empty").classList.add("hidden");
+  }
+}
+
+function showMessage(message) {
+  const empty = el("history-empty");
+  empty.textContent = message;
+  empty.classList.remove("hidden");
+}
+
+async function load(append) {
+  try {
+    if (query) {
+      entries = await invoke(COMMANDS.SEARCH_HISTORY, { query, limit: SEARCH_LIMIT });
+      hasMore = false;
+    } else {
+      const offset = append ? entries.length : 0;
+      const page = await invoke(COMMANDS.LIST_HISTORY, { offset, limit: PAGE_SIZE });
+      entries = append ? entries.concat(page) : page;
+      hasMore = page.length === PAGE_SIZE;
+    }
+    render();
+  } catch (e) {
+    entries = [];
+    hasMore = false;
+    el("history-list").replaceChildren();
+    el("history-more").classList.add("hidden");
+    showMessage(String(e));
+  }
+}
+
+window.addEventListener("DOMContentLoaded", async () => {
+  try {
+    const s = await invoke(COMMANDS.GET_SETTINGS);
+    applyLocale(s.ui_locale || "en");
+  } catch (_) {}
+
+  el("history-search").addEventListener("input", () => {
+    clearTimeout(searchTimer);
+    searchTimer = setTimeout(() => {
+      query = el("history-search").value.trim();
+      load(false);
+    }, 250);
+  });
+  el("history-more").addEventListener("click", () => load(true));
+
+  await listen(EVENTS.HISTORY_CHANGED, () => load(false));
+  await load(false);
+});
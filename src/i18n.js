@@ -12,16 +12,25 @@ const I18N = {
     "row.engine": "Engine",
     "row.model": "Model",
     "row.dictPacks": "Dictionary Packs",
+    "row.autoLanguages": "Expected Languages",
     "row.dict": "Custom Dictionary",
+    "row.hallucinationFilter": "Hallucination Filter",
     "row.llm": "LLM Post-Processing",
     "row.provider": "Provider",
     "row.apiKey": "API Key",
     "row.url": "URL",
     "row.endpoint": "Endpoint",
     "row.appAware": "App-Aware Style",
+    "row.vad": "Trim Silence",
     "row.mode": "Mode",
     "row.opacity": "Opacity",
     "row.autoStart": "Launch at Login",
+    "row.microphone": "Microphone",
+    "mic.systemDefault": "System Default",
+    "mic.disconnected": "{name} (not connected)",
+    "row.preRoll": "Pre-roll Buffer",
+    "row.preRollLength": "Buffer Length",
+    "hint.preRoll": "Keeps the microphone open so the first syllable isn't cut off. The last moment of audio is held in memory only and discarded unless you start recording; your system's microphone indicator stays on.",
     "row.updates": "Updates",
     "row.changelog": "What's New",
     "row.uiLocale": "UI Language",
@@ -31,11 +40,52 @@ const I18N = {
     "btn.save": "Save",
     "btn.holdToTalk": "Hold to Talk",
     "btn.pressToToggle": "Press to Toggle",
+    "btn.longForm": "Long-form",
+    "row.longFormLimit": "Stop After (min)",
+    "row.longFormKeepAudio": "Keep Audio",
+    "hint.longForm": "For meetings and lectures: press once to start and again to stop. Speech is transcribed in chunks as you go and the audio is discarded once transcribed, unless you keep it as a WAV file in the system temp folder. Set the limit to 0 to record without one.",
+    "group.history": "History",
+    "row.historyEnabled": "Save History",
+    "row.historyDir": "Location",
+    "row.historyRetention": "Keep For (days)",
+    "row.historyBrowse": "Past Dictations",
+    "btn.choose": "Choose…",
+    "btn.openHistory": "Open History",
+    "hint.history": "When on, each result is stored on this computer with its raw and processed text. Entries older than the limit are deleted; set it to 0 to keep everything. Changing the location moves the existing history there.",
     "hint.groqKey": "API Key is in AI Processing below",
+    "hint.customEngine": "Any OpenAI-compatible /v1/audio/transcriptions server, e.g. a self-hosted faster-whisper",
+    "model.diskUsage": "Models use {size}",
+    "model.download": "Download",
+    "model.cancelDownload": "Cancel ({percent}%)",
+    "model.delete": "Delete",
+    "model.import": "Import…",
+    "model.imported": "Model imported",
+    "row.modelMirror": "Mirror",
+    "row.accuracy": "Accuracy",
+    "accuracy.fast": "Fast",
+    "accuracy.balanced": "Balanced",
+    "accuracy.accurate": "Accurate (beam search)",
+    "accuracy.custom": "Custom",
+    "hint.accuracy": "Accurate decodes slower but helps with noise and accents",
+    "row.decodeBeamSize": "Beam Size",
+    "row.decodeBestOf": "Best Of",
+    "row.decodeTemperatureInc": "Temperature Step",
+    "row.decodeEntropy": "Entropy Threshold",
+    "row.decodeLogprob": "Log Prob Threshold",
+    "row.decodeNoSpeech": "No-Speech Threshold",
+    "row.decodeConfidence": "Min Confidence",
+    "row.decodeThreads": "Threads (0 = auto)",
     "hint.llm": "Auto-clean filler words, add punctuation, fix grammar after transcription",
     "hint.appAware": "Auto-adjust tone based on active app (e.g. terminal \u2192 technical style)",
+    "hint.vad": "Skip pauses and silence before transcribing for faster, cleaner results",
+    "hint.autoLanguages": "Auto Detect only picks from the checked languages (local model). Leave all unchecked to allow any",
     "hint.ollama": "Runs locally, fully offline. Requires Ollama installed.",
     "dict.placeholder": "Type a term and press Enter",
+    "hint.hallucinationFilter": "Results matching a checked phrase are discarded as Whisper hallucinations. Uncheck phrases you actually say",
+    "filter.exact": "Exact",
+    "filter.contains": "Contains",
+    "filter.regex": "Regex",
+    "filter.placeholder": "Add a phrase and press Enter",
     "vision.tagline": "Your voice, unheard by others.",
     "vision.roadmap": "Roadmap",
     "onboard.welcome": "Welcome to Murmur Voice",
@@ -93,6 +143,7 @@ const I18N = {
     "state.ready": "Ready",
     "state.starting": "Starting...",
     "state.listening": "Listening...",
+    "state.micQuiet": "Mic too quiet",
     "state.stopping": "Stopping...",
     "state.transcribing": "Transcribing...",
     "state.processing": "Processing...",
@@ -100,6 +151,11 @@ const I18N = {
     "state.error": "Error",
     "state.cancelled": "Cancelled",
     "state.downloadingModel": "Downloading model... {pct}%",
+    "state.connecting": "Connecting...",
+    "state.eta": "{time} left",
+    "state.verifyingModel": "Verifying model...",
+    "state.loadingModel": "Loading model...",
+    "state.warmingUp": "Warming up...",
     "state.downloadFailed": "Download failed",
     "state.accessibilityError": "Accessibility required",
     "state.accessibilityHint": "Grant permission in System Settings > Privacy & Security > Accessibility. It will auto-connect.",
@@ -107,6 +163,25 @@ const I18N = {
     "preview.noSpeech": "No speech detected",
     "preview.copy": "Copy",
     "preview.copied": "Copied!",
+    "preview.save": "Save…",
+    "preview.saved": "Saved!",
+    "preview.saveFilter": "Transcript",
+    "history.title": "History",
+    "history.search": "Search",
+    "history.empty": "No dictations yet",
+    "history.noResults": "No matches",
+    "history.more": "Load More",
+    "history.raw": "Raw transcription",
+    "history.copy": "Copy",
+    "history.copied": "Copied!",
+    "history.paste": "Paste",
+    "history.delete": "Delete",
+    "history.defaultDir": "App data folder",
+    "history.mode.Dictation": "Dictation",
+    "history.mode.Translate": "Translate",
+    "history.mode.VoiceCommand": "Voice Command",
+    "history.mode.ClipboardRewrite": "Clipboard Rewrite",
+    "history.mode.SpeechToEnglish": "Speak to English",
     "preview.nChars": "{n} chars",
     "preview.dictPrompt": "Add \"{word}\" to dictionary?",
     "preview.dictAdded": "Added!",
@@ -119,18 +194,22 @@ const I18N = {
     "row.hotkeyTranslate": "Translate",
     "row.hotkeyVoiceCommand": "Voice Command",
     "row.hotkeyClipboardRewrite": "Clipboard Rewrite",
+    "row.hotkeySpeechToEnglish": "Speak to English",
     "hotkey.notSet": "Not set",
     "hint.voiceCommand": "Speak a command to process selected text with AI",
     "hint.clipboardRewrite": "Speak a command to process clipboard content with AI",
+    "hint.speechToEnglish": "Speak any language and get English text, offline with the local model",
     // Recording mode info
     "mode.dictation": "Voice dictation",
     "mode.dictation_llm": "Voice dictation with AI enhancement",
     "mode.voice_command": "Voice command on selected text",
     "mode.clipboard_rewrite": "Voice command on clipboard",
+    "mode.speech_to_english": "Speak any language, get English",
     // Preview mode badges
     "preview.mode.dictated": "Dictated",
     "preview.mode.voice_command": "Voice Command",
     "preview.mode.clipboard_rewrite": "Clipboard Rewrite",
+    "preview.mode.speech_to_english": "Spoken to English",
     // Translation
     "group.translation": "Translation",
     "row.translateHotkey": "Translate Hotkey",
@@ -154,16 +233,25 @@ const I18N = {
     "row.engine": "引擎",
     "row.model": "模型",
     "row.dictPacks": "辭典包",
+    "row.autoLanguages": "預期語言",
     "row.dict": "自訂辭典",
+    "row.hallucinationFilter": "幻覺過濾",
     "row.llm": "LLM 後處理",
     "row.provider": "供應商",
     "row.apiKey": "API Key",
     "row.url": "URL",
     "row.endpoint": "端點",
     "row.appAware": "應用感知風格",
+    "row.vad": "略過靜音",
     "row.mode": "模式",
     "row.opacity": "透明度",
     "row.autoStart": "登入時啟動",
+    "row.microphone": "麥克風",
+    "mic.systemDefault": "系統預設",
+    "mic.disconnected": "{name}（未連接）",
+    "row.preRoll": "預錄緩衝",
+    "row.preRollLength": "緩衝長度",
+    "hint.preRoll": "讓麥克風保持開啟，避免第一個字被截掉。最後片刻的音訊只暫存在記憶體中，未開始錄音就會丟棄；系統的麥克風指示燈會持續亮著。",
     "row.updates": "更新",
     "row.changelog": "新功能",
     "row.uiLocale": "介面語言",
@@ -173,11 +261,52 @@ const I18N = {
     "btn.save": "儲存",
     "btn.holdToTalk": "按住說話",
     "btn.pressToToggle": "點按開關",
+    "btn.longForm": "長時間錄音",
+    "row.longFormLimit": "自動停止（分鐘）",
+    "row.longFormKeepAudio": "保留音訊",
+    "hint.longForm": "適合會議與課堂：按一次開始，再按一次停止。語音會邊錄邊分段轉錄，轉錄完的音訊隨即丟棄；開啟保留音訊則會存成 WAV 檔放在系統暫存資料夾。上限設為 0 即不限時間。",
+    "group.history": "歷史紀錄",
+    "row.historyEnabled": "保存歷史紀錄",
+    "row.historyDir": "位置",
+    "row.historyRetention": "保留天數",
+    "row.historyBrowse": "過去的聽寫",
+    "btn.choose": "選擇…",
+    "btn.openHistory": "開啟歷史紀錄",
+    "hint.history": "開啟後，每筆結果都會連同原始與處理後的文字存在這台電腦上。超過保留天數的紀錄會被刪除；設為 0 即全部保留。變更位置時，現有紀錄會一併搬過去。",
     "hint.groqKey": "API Key 在下方 AI 處理區",
+    "hint.customEngine": "任何相容 OpenAI /v1/audio/transcriptions 的伺服器，例如自架的 faster-whisper",
+    "model.diskUsage": "模型佔用 {size}",
+    "model.download": "下載",
+    "model.cancelDownload": "取消（{percent}%）",
+    "model.delete": "刪除",
+    "model.import": "匯入…",
+    "model.imported": "已匯入模型",
+    "row.modelMirror": "鏡像站",
+    "row.accuracy": "準確度",
+    "accuracy.fast": "快速",
+    "accuracy.balanced": "平衡",
+    "accuracy.accurate": "精準（束搜尋）",
+    "accuracy.custom": "自訂",
+    "hint.accuracy": "精準模式解碼較慢，但在吵雜環境或口音下更準確",
+    "row.decodeBeamSize": "束寬",
+    "row.decodeBestOf": "候選數",
+    "row.decodeTemperatureInc": "溫度遞增",
+    "row.decodeEntropy": "熵閾值",
+    "row.decodeLogprob": "對數機率閾值",
+    "row.decodeNoSpeech": "無語音閾值",
+    "row.decodeConfidence": "最低信心度",
+    "row.decodeThreads": "執行緒（0 = 自動）",
     "hint.llm": "語音轉文字後，AI 自動去贅詞、加標點、修文法",
     "hint.appAware": "根據目前 app 自動調整語氣，如終端機 \u2192 技術風格",
+    "hint.vad": "轉錄前略過停頓與靜音，速度更快、結果更乾淨",
+    "hint.autoLanguages": "自動偵測只會從勾選的語言中選擇（本機模型）。全部不勾選則不限制",
     "hint.ollama": "本地執行，完全離線，需先安裝 Ollama",
     "dict.placeholder": "輸入詞彙後按 Enter",
+    "hint.hallucinationFilter": "符合勾選詞句的結果會被視為 Whisper 幻覺而捨棄。取消勾選你實際會說的詞句",
+    "filter.exact": "完全相符",
+    "filter.contains": "包含",
+    "filter.regex": "正規表示式",
+    "filter.placeholder": "輸入詞句後按 Enter",
     "vision.tagline": "你的聲音，不被他人聽見。",
     "vision.roadmap": "產品路線圖",
     "onboard.welcome": "歡迎使用 Murmur Voice",
@@ -233,6 +362,7 @@ const I18N = {
     "state.ready": "就緒",
     "state.starting": "啟動中...",
     "state.listening": "聆聽中...",
+    "state.micQuiet": "麥克風音量過低",
     "state.stopping": "停止中...",
     "state.transcribing": "轉錄中...",
     "state.processing": "處理中...",
@@ -240,12 +370,36 @@ const I18N = {
     "state.error": "錯誤",
     "state.cancelled": "已取消",
     "state.downloadingModel": "下載模型中... {pct}%",
+    "state.connecting": "連線中...",
+    "state.eta": "剩餘 {time}",
+    "state.verifyingModel": "驗證模型中...",
+    "state.loadingModel": "載入模型中...",
+    "state.warmingUp": "暖機中...",
     "state.downloadFailed": "下載失敗",
     "state.accessibilityError": "需要輔助使用權限",
     "state.accessibilityHint": "請至系統設定 > 隱私權與安全性 > 輔助使用 開啟權限，開啟後會自動連線。",
     "preview.noSpeech": "未偵測到語音",
     "preview.copy": "複製",
     "preview.copied": "已複製!",
+    "preview.save": "儲存…",
+    "preview.saved": "已儲存!",
+    "preview.saveFilter": "逐字稿",
+    "history.title": "歷史紀錄",
+    "history.search": "搜尋",
+    "history.empty": "尚無聽寫紀錄",
+    "history.noResults": "找不到符合的紀錄",
+    "history.more": "載入更多",
+    "history.raw": "原始轉錄",
+    "history.copy": "複製",
+    "history.copied": "已複製!",
+    "history.paste": "貼上",
+    "history.delete": "刪除",
+    "history.defaultDir": "應用程式資料夾",
+    "history.mode.Dictation": "聽寫",
+    "history.mode.Translate": "翻譯",
+    "history.mode.VoiceCommand": "語音指令",
+    "history.mode.ClipboardRewrite": "剪貼簿改寫",
+    "history.mode.SpeechToEnglish": "說話轉英文",
     "preview.nChars": "{n} 字元",
     "preview.dictPrompt": "加入「{word}」到辭典？",
     "preview.dictAdded": "已加入!",
@@ -258,16 +412,20 @@ const I18N = {
     "row.hotkeyTranslate": "翻譯",
     "row.hotkeyVoiceCommand": "語音指令",
     "row.hotkeyClipboardRewrite": "剪貼簿改寫",
+    "row.hotkeySpeechToEnglish": "說話轉英文",
     "hotkey.notSet": "未設定",
     "hint.voiceCommand": "對選取文字說出指令，由 AI 處理",
     "hint.clipboardRewrite": "對剪貼簿內容說出指令，由 AI 處理",
+    "hint.speechToEnglish": "說任何語言都輸出英文，使用本機模型離線處理",
     "mode.dictation": "語音輸入",
     "mode.dictation_llm": "語音輸入，大模型文本優化",
     "mode.voice_command": "語音指令，處理選取文字",
     "mode.clipboard_rewrite": "語音指令，處理剪貼簿內容",
+    "mode.speech_to_english": "說任何語言，輸出英文",
     "preview.mode.dictated": "語音輸入",
     "preview.mode.voice_command": "語音指令",
     "preview.mode.clipboard_rewrite": "剪貼簿改寫",
+    "preview.mode.speech_to_english": "已轉為英文",
     "group.translation": "翻譯",
     "row.translateHotkey": "翻譯快捷鍵",
     "row.translateLang": "目標語言",
@@ -8,6 +8,8 @@ let progressContainer;
 let progressBar;
 let appBadge;
 let appEl;
+let levelMeter;
+let levelBar;
 let isCollapsing = false;
 let recordingMaxHeight = 0;
 const MAIN_BAR_HEIGHT = 48;
@@ -22,6 +24,16 @@ function setStatus(state, text) {
   statusText.textContent = text;
 }
 
+function formatSpeed(bytesPerSec) {
+  return (bytesPerSec / 1e6).toFixed(1) + " MB/s";
+}
+
+function formatEta(secs) {
+  const m = Math.floor(secs / 60);
+  const s = String(secs % 60).padStart(2, "0");
+  return `${m}:${s}`;
+}
+
 let expandPending = false;
 
 function expandMainBar() {
@@ -82,6 +94,19 @@ function collapseMainBar() {
   }, 170);
 }
 
+// Map RMS to a -60..0 dBFS meter
+function levelToPercent(rms) {
+  if (rms <= 0) return 0;
+  const db = 20 * Math.log10(rms);
+  return Math.max(0, Math.min(100, ((db + 60) / 60) * 100));
+}
+
+function showLevelMeter(visible) {
+  levelMeter.classList.toggle("hidden", !visible);
+  levelMeter.classList.remove("clipping");
+  levelBar.style.width = "0%";
+}
+
 function resetMainBar() {
   isCollapsing = false;
   expandPending = false;
@@ -99,6 +124,8 @@ window.addEventListener("DOMContentLoaded", async () => {
   progressBar = document.getElementById("progress-bar");
   appBadge = document.getElementById("app-badge");
   appEl = document.getElementById("app");
+  levelMeter = document.getElementById("level-meter");
+  levelBar = document.getElementById("level-bar");
 
   // Load locale
   try {
@@ -108,11 +135,42 @@ window.addEventListener("DOMContentLoaded", async () => {
 
   // Register ALL event listeners FIRST, before triggering any commands.
   await listen(EVENTS.MODEL_DOWNLOAD_PROGRESS, (event) => {
-    const { downloaded, total } = event.payload;
-    const pct = total > 0 ? (downloaded / total) * 100 : 0;
+    const { stage, downloaded, total, bytes_per_sec, eta_secs } = event.payload;
+    // A model switch can reload the engine mid-dictation; keep its status
+    if (statusDot.classList.contains("recording") || statusDot.classList.contains("transcribing")) return;
     progressContainer.classList.remove("hidden");
-    progressBar.style.width = pct + "%";
-    setStatus(null, t("state.downloadingModel").replace("{pct}", Math.round(pct)));
+    switch (stage) {
+      case "connecting":
+        setStatus(null, t("state.connecting"));
+        break;
+      case "downloading": {
+        const pct = total > 0 ? (downloaded / total) * 100 : 0;
+        progressBar.style.width = pct + "%";
+        let text = t("state.downloadingModel").replace("{pct}", Math.round(pct));
+        if (bytes_per_sec) text += " · " + formatSpeed(bytes_per_sec);
+        if (eta_secs != null) text += " · " + t("state.eta").replace("{time}", formatEta(eta_secs));
+        setStatus(null, text);
+        break;
+      }
+      case "verifying":
+        setStatus(null, t("state.verifyingModel"));
+        break;
+      case "loading":
+        progressBar.style.width = "100%";
+        setStatus(null, t("state.loadingModel"));
+        break;
+      case "warming_up":
+        progressBar.style.width = "100%";
+        setStatus(null, t("state.warmingUp"));
+        break;
+    }
+  });
+
+  await listen(EVENTS.ENGINE_READY, () => {
+    if (statusDot.classList.contains("recording") || statusDot.classList.contains("transcribing")) return;
+    progressContainer.classList.add("hidden");
+    progressBar.style.width = "0%";
+    setStatus(null, t("state.ready"));
   });
 
   await listen(EVENTS.MODEL_READY, () => {
@@ -121,6 +179,12 @@ window.addEventListener("DOMContentLoaded", async () => {
     setStatus(null, t("state.ready"));
   });
 
+  await listen(EVENTS.MODEL_DOWNLOAD_CANCELLED, () => {
+    progressContainer.classList.add("hidden");
+    progressBar.style.width = "0%";
+    setStatus(null, t("state.ready"));
+  });
+
   await listen(EVENTS.RECORDING_STATE_CHANGED, (event) => {
     const state = event.payload;
     switch (state) {
@@ -131,9 +195,11 @@ window.addEventListener("DOMContentLoaded", async () => {
         break;
       case RECORDING_STATES.RECORDING:
         setStatus("recording", t("state.listening"));
+        showLevelMeter(true);
         break;
       case RECORDING_STATES.STOPPING:
         setStatus("transcribing", t("state.stopping"));
+        showLevelMeter(false);
         break;
       case RECORDING_STATES.TRANSCRIBING:
         setStatus("transcribing", t("state.transcribing"));
@@ -148,6 +214,7 @@ window.addEventListener("DOMContentLoaded", async () => {
         break;
       case RECORDING_STATES.IDLE:
         setStatus(null, t("state.ready"));
+        showLevelMeter(false);
         appBadge.classList.remove("visible");
         appBadge.textContent = "";
         if (expandPending || appEl.classList.contains("expanded")) {
@@ -161,6 +228,15 @@ window.addEventListener("DOMContentLoaded", async () => {
     }
   });
 
+  // Input level while recording (~20 updates/s)
+  await listen(EVENTS.AUDIO_LEVEL, (event) => {
+    const { rms, clipping, too_quiet } = event.payload;
+    if (levelMeter.classList.contains("hidden")) return;
+    levelBar.style.width = levelToPercent(rms) + "%";
+    levelMeter.classList.toggle("clipping", clipping);
+    setStatus("recording", t(too_quiet ? "state.micQuiet" : "state.listening"));
+  });
+
   // Live transcription updates while recording
   await listen(EVENTS.PARTIAL_TRANSCRIPTION, (event) => {
     transcription.textContent = event.payload;
@@ -213,6 +289,7 @@ window.addEventListener("DOMContentLoaded", async () => {
   await listen(EVENTS.RECORDING_ERROR, (event) => {
     const errorMsg = event.payload;
     resetMainBar();
+    showLevelMeter(false);
     transcription.textContent = errorMsg;
     setStatus("error", t("state.error"));
 
@@ -7,6 +7,7 @@ const charCount = () => document.getElementById("char-count");
 const appBadge = () => document.getElementById("app-badge");
 const previewBody = () => document.getElementById("preview-body");
 const copyBtn = () => document.getElementById("copy-btn");
+const saveBtn = () => document.getElementById("save-btn");
 const closeBtn = () => document.getElementById("close-btn");
 const translateBtn = () => document.getElementById("translate-btn");
 const dictSuggest = () => document.getElementById("dict-suggest");
@@ -15,6 +16,7 @@ const dictChips = () => document.getElementById("dict-chips");
 let autoHideTimer = null;
 let dotsInterval = null;
 let currentMode = null;
+let spokenLanguage = null;
 let originalText = "";
 let addedWords = new Set();
 let dismissedWords = new Set();
@@ -181,6 +183,7 @@ function reset() {
   setAppBadge(null);
   disableEditing();
   copyBtn().classList.add("hidden");
+  saveBtn().classList.add("hidden");
   closeBtn().classList.add("hidden");
   translateBtn().classList.add("hidden");
   hideDictSuggest();
@@ -224,6 +227,30 @@ window.addEventListener("DOMContentLoaded", async () => {
     }
   });
 
+  // Save button handler: the backend picks the format from the extension
+  saveBtn().addEventListener("click", async () => {
+    const btn = saveBtn();
+    const text = previewText().textContent;
+    clearAutoHide();
+    try {
+      const path = await window.__TAURI__.dialog.save({
+        defaultPath: "transcript.srt",
+        filters: [{ name: t("preview.saveFilter"), extensions: ["srt", "vtt", "json", "md"] }],
+      });
+      if (!path) return;
+      await invoke(COMMANDS.SAVE_TRANSCRIPT, { path, text });
+      btn.textContent = t("preview.saved");
+      btn.classList.add("saved");
+      setTimeout(() => {
+        btn.textContent = t("preview.save");
+        btn.classList.remove("saved");
+      }, 1500);
+    } catch (e) {
+      setHeader(t("state.error"), false);
+      console.error("save_transcript failed:", e);
+    }
+  });
+
   // Close button handler
   closeBtn().addEventListener("click", () => {
     invoke(COMMANDS.HIDE_OVERLAY_WINDOWS).catch(() => {});
@@ -242,7 +269,7 @@ window.addEventListener("DOMContentLoaded", async () => {
     clearAutoHide();
 
     try {
-      const translated = await invoke(COMMANDS.TRANSLATE_TEXT, { text });
+      const translated = await invoke(COMMANDS.TRANSLATE_TEXT, { text, language: spokenLanguage });
       setText(translated, null);
       setCharCount(translated);
       scrollToBottom();
@@ -339,6 +366,7 @@ window.addEventListener("DOMContentLoaded", async () => {
         setText("", null);
         setCharCount("");
         copyBtn().classList.add("hidden");
+        saveBtn().classList.add("hidden");
         closeBtn().classList.add("hidden");
         translateBtn().classList.add("hidden");
         disableEditing();
@@ -359,6 +387,7 @@ window.addEventListener("DOMContentLoaded", async () => {
     setHeader(t("state.error"), false);
     setText(event.payload, "no-speech");
     copyBtn().classList.add("hidden");
+    saveBtn().classList.add("hidden");
     closeBtn().classList.remove("hidden");
     translateBtn().classList.add("hidden");
     disableEditing();
@@ -379,15 +408,17 @@ window.addEventListener("DOMContentLoaded", async () => {
   });
 
   await listen(EVENTS.TRANSCRIPTION_COMPLETE, (event) => {
-    const { text, mode } = event.payload;
+    const { text, mode, language } = event.payload;
     clearAutoHide();
     currentMode = mode;
+    spokenLanguage = language || null;
 
     if (!text || text.trim().length === 0) {
       setHeader(t("state.done"), false);
       setText(t("preview.noSpeech"), "no-speech");
       setCharCount("");
       copyBtn().classList.add("hidden");
+      saveBtn().classList.add("hidden");
       closeBtn().classList.add("hidden");
       disableEditing();
       // Auto-hide after 3s for empty results
@@ -404,12 +435,15 @@ window.addEventListener("DOMContentLoaded", async () => {
         setHeader(t("preview.mode.voice_command"), false);
       } else if (mode === TRANSCRIPTION_MODES.CLIPBOARD_REWRITE) {
         setHeader(t("preview.mode.clipboard_rewrite"), false);
+      } else if (mode === TRANSCRIPTION_MODES.SPEECH_TO_ENGLISH) {
+        setHeader(t("preview.mode.speech_to_english"), false);
       }
       setText(text, null);
       setCharCount(text);
       scrollToBottom();
       originalText = text;
       copyBtn().classList.remove("hidden");
+      saveBtn().classList.remove("hidden");
       closeBtn().classList.remove("hidden");
       if (mode === TRANSCRIPTION_MODES.DICTATED) {
         translateBtn().classList.remove("hidden");
@@ -1,5 +1,6 @@
 const { invoke } = window.__TAURI__.core;
 const { getCurrentWindow } = window.__TAURI__.window;
+const { listen } = window.__TAURI__.event;
 
 // Key mapping: JS event.code → display name
 const KEY_MAP = {
@@ -54,6 +55,7 @@ let replaceUndoEntry = null; // { rule, index }
 let currentTranslateKey = "AltLeft+KeyT";
 let currentVoiceCommandKey = "";
 let currentClipboardRewriteKey = "";
+let currentSpeechToEnglishKey = "";
 let activeComboRecorder = null; // { btnId, setter, modifiers }
 
 const el = (id) => document.getElementById(id);
@@ -191,12 +193,85 @@ function setRecordingMode(mode) {
   btns.forEach((btn) => {
     btn.classList.toggle("active", btn.dataset.value === mode);
   });
+  el("long-form-section").classList.toggle("hidden", mode !== "long_form");
+}
+
+// Checkbox per language from the language dropdown, for auto-detect candidates
+function buildAutoLanguages() {
+  const container = el("auto-languages");
+  container.innerHTML = "";
+  el("language").querySelectorAll("option").forEach((opt) => {
+    if (opt.value === "auto") return;
+    const label = document.createElement("label");
+    label.className = "dict-pack-toggle";
+    const cb = document.createElement("input");
+    cb.type = "checkbox";
+    cb.value = opt.value;
+    const span = document.createElement("span");
+    span.textContent = opt.textContent;
+    label.append(cb, span);
+    container.appendChild(label);
+  });
+}
+
+function getAutoLanguages() {
+  const codes = [];
+  el("auto-languages").querySelectorAll("input[type=checkbox]").forEach((cb) => {
+    if (cb.checked) codes.push(cb.value);
+  });
+  return codes;
+}
+
+function loadAutoLanguages(codes) {
+  el("auto-languages").querySelectorAll("input[type=checkbox]").forEach((cb) => {
+    cb.checked = codes.includes(cb.value);
+  });
+}
+
+function updateLanguageVisibility() {
+  el("auto-languages-section").classList.toggle("hidden", el("language").value !== "auto");
+}
+
+// Custom decoding inputs, by Settings.decoding field
+const DECODING_INPUTS = {
+  beam_size: "beam-size",
+  best_of: "best-of",
+  temperature_inc: "temperature-inc",
+  entropy_threshold: "entropy-threshold",
+  logprob_threshold: "logprob-threshold",
+  no_speech_threshold: "no-speech-threshold",
+  confidence_threshold: "confidence-threshold",
+  threads: "decode-threads",
+};
+
+function loadDecoding(decoding) {
+  for (const [field, id] of Object.entries(DECODING_INPUTS)) {
+    if (decoding[field] !== undefined) el(id).value = String(decoding[field]);
+  }
+}
+
+// Empty inputs are left out and take the default; ranges are checked on save
+function getDecoding() {
+  const decoding = {};
+  for (const [field, id] of Object.entries(DECODING_INPUTS)) {
+    const value = el(id).value.trim();
+    if (value !== "" && Number.isFinite(Number(value))) decoding[field] = Number(value);
+  }
+  return decoding;
+}
+
+function updateAccuracyVisibility() {
+  el("decoding-custom").classList.toggle("hidden", el("accuracy-profile").value !== "custom");
 }
 
 function updateEngineVisibility() {
-  const isLocal = el("engine").value === "local";
-  el("model-section").classList.toggle("hidden", !isLocal);
-  el("groq-section").classList.toggle("hidden", isLocal);
+  const engine = el("engine").value;
+  el("model-section").classList.toggle("hidden", engine !== "local");
+  el("model-actions-row").classList.toggle("hidden", engine !== "local");
+  el("model-mirror-row").classList.toggle("hidden", engine !== "local");
+  el("accuracy-section").classList.toggle("hidden", engine !== "local");
+  el("groq-section").classList.toggle("hidden", engine !== "groq");
+  el("custom-engine-section").classList.toggle("hidden", engine !== "custom");
 }
 
 function updateLlmProviderVisibility() {
@@ -300,6 +375,84 @@ function loadDictPacks(packs) {
   });
 }
 
+// --- Hallucination filter ---
+
+let filterAdded = [];
+let filterRemoved = [];
+
+// Built-in phrases become checkboxes (checked = active). Built-in regex
+// rules aren't shown; removing them is left to settings.json.
+async function loadHallucinationFilter(filter) {
+  filterAdded = filter.added || [];
+  filterRemoved = (filter.removed || []).map((p) => p.toLowerCase());
+  let builtin = [];
+  try {
+    builtin = await invoke(COMMANDS.GET_HALLUCINATION_RULES);
+  } catch (e) {
+    console.error("get_hallucination_rules failed:", e);
+  }
+  const container = el("filter-builtin");
+  container.innerHTML = "";
+  builtin
+    .filter((rule) => rule.kind !== "regex")
+    .forEach((rule) => {
+      const label = document.createElement("label");
+      label.className = "dict-pack-toggle";
+      const cb = document.createElement("input");
+      cb.type = "checkbox";
+      cb.value = rule.pattern;
+      cb.checked = !filterRemoved.includes(rule.pattern.toLowerCase());
+      const span = document.createElement("span");
+      span.textContent = rule.pattern;
+      label.append(cb, span);
+      container.appendChild(label);
+    });
+  renderFilterTags();
+}
+
+function renderFilterTags() {
+  const container = el("filter-tags");
+  container.innerHTML = "";
+  filterAdded.forEach((rule, i) => {
+    const tag = document.createElement("span");
+    tag.className = "dict-tag";
+    tag.textContent = rule.kind === "exact" ? rule.pattern : `${rule.kind}: ${rule.pattern}`;
+
+    const x = document.createElement("button");
+    x.className = "dict-tag-x";
+    x.textContent = "\u00d7";
+    x.addEventListener("click", () => {
+      filterAdded.splice(i, 1);
+      renderFilterTags();
+    });
+
+    tag.appendChild(x);
+    container.appendChild(tag);
+  });
+  el("filter-count").textContent = filterAdded.length > 0 ? t("dict.nTerms").replace("{n}", filterAdded.length) : "";
+}
+
+function addFilterRule(pattern) {
+  const cleaned = pattern.trim();
+  if (!cleaned) return;
+  const kind = el("filter-kind").value;
+  if (filterAdded.some((r) => r.kind === kind && r.pattern === cleaned)) return;
+  filterAdded.push({ kind, pattern: cleaned });
+  renderFilterTags();
+}
+
+function getHallucinationFilter() {
+  const removed = [];
+  const shown = new Set();
+  el("filter-builtin").querySelectorAll("input[type=checkbox]").forEach((cb) => {
+    shown.add(cb.value.toLowerCase());
+    if (!cb.checked) removed.push(cb.value);
+  });
+  // Keep removals of rules without a checkbox
+  filterRemoved.filter((p) => !shown.has(p)).forEach((p) => removed.push(p));
+  return { added: filterAdded, removed };
+}
+
 function getDictString() {
   return dictTags.join(", ");
 }
@@ -410,6 +563,164 @@ function collectReplacements() {
   }));
 }
 
+// --- Input Device ---
+
+let selectedInputDevice = "";
+
+// Rebuild the microphone list. A saved device that is currently unplugged
+// stays selectable so the choice isn't lost; recording falls back to default.
+async function loadInputDevices() {
+  const select = el("input-device");
+  if (select.options.length > 0 && select.dataset.loaded) {
+    selectedInputDevice = select.value;
+  }
+  let devices = [];
+  try {
+    devices = await invoke(COMMANDS.LIST_INPUT_DEVICES);
+  } catch (e) {
+    console.error("Failed to list input devices:", e);
+  }
+
+  select.innerHTML = "";
+  const defaultOpt = document.createElement("option");
+  defaultOpt.value = "";
+  defaultOpt.dataset.i18n = "mic.systemDefault";
+  defaultOpt.textContent = t("mic.systemDefault");
+  select.appendChild(defaultOpt);
+
+  for (const device of devices) {
+    const opt = document.createElement("option");
+    opt.value = device.name;
+    opt.textContent = device.name;
+    select.appendChild(opt);
+  }
+
+  if (selectedInputDevice && !devices.some(d => d.name === selectedInputDevice)) {
+    const opt = document.createElement("option");
+    opt.value = selectedInputDevice;
+    opt.textContent = t("mic.disconnected").replace("{name}", selectedInputDevice);
+    select.appendChild(opt);
+  }
+  select.value = selectedInputDevice;
+  select.dataset.loaded = "true";
+}
+
+// --- Model Manager ---
+
+let modelCatalog = { models: [], active: "", disk_usage: 0 };
+let downloadingModel = null;
+
+function formatBytes(bytes) {
+  if (bytes >= 1e9) return (bytes / 1e9).toFixed(1) + " GB";
+  return Math.round(bytes / 1e6) + " MB";
+}
+
+async function loadModels() {
+  const select = el("model");
+  const selected = select.value || modelCatalog.active;
+  try {
+    modelCatalog = await invoke(COMMANDS.LIST_MODELS);
+  } catch (e) {
+    console.error("Failed to list models:", e);
+    return;
+  }
+
+  select.innerHTML = "";
+  for (const m of modelCatalog.models) {
+    const opt = document.createElement("option");
+    opt.value = m.id;
+    opt.textContent = `${m.name} · ${formatBytes(m.size)}` + (m.installed ? " ✓" : "");
+    select.appendChild(opt);
+  }
+  select.value = selected || modelCatalog.active;
+  el("model-disk-usage").textContent = t("model.diskUsage").replace("{size}", formatBytes(modelCatalog.disk_usage));
+  updateModelAction();
+}
+
+function updateModelAction() {
+  const btn = el("btn-model-action");
+  const m = modelCatalog.models.find((m) => m.id === el("model").value);
+  if (downloadingModel) {
+    btn.classList.toggle("hidden", downloadingModel !== el("model").value);
+    return;
+  }
+  if (!m) {
+    btn.classList.add("hidden");
+  } else if (!m.installed) {
+    btn.classList.remove("hidden");
+    btn.textContent = t("model.download");
+  } else if (m.id !== modelCatalog.active) {
+    btn.classList.remove("hidden");
+    btn.textContent = t("model.delete");
+  } else {
+    btn.classList.add("hidden");
+  }
+}
+
+async function onModelAction() {
+  const id = el("model").value;
+  const m = modelCatalog.models.find((m) => m.id === id);
+  if (!m) return;
+  if (downloadingModel) {
+    await invoke(COMMANDS.CANCEL_MODEL_DOWNLOAD);
+    return;
+  }
+  try {
+    if (m.installed) {
+      await invoke(COMMANDS.DELETE_MODEL, { model: id });
+    } else {
+      downloadingModel = id;
+      updateModelAction();
+      el("btn-model-action").textContent = t("model.cancelDownload").replace("{percent}", "0");
+      await invoke(COMMANDS.DOWNLOAD_MODEL_CMD, { model: id });
+    }
+  } catch (e) {
+    showStatus(String(e), true);
+  }
+  downloadingModel = null;
+  await loadModels();
+}
+
+async function onModelImport() {
+  const path = await window.__TAURI__.dialog.open({
+    multiple: false,
+    filters: [{ name: "ggml", extensions: ["bin"] }],
+  });
+  if (!path) return;
+  // Files with a catalog name import as that model, others as the selected one
+  const filename = path.split(/[\\/]/).pop();
+  const known = modelCatalog.models.some((m) => m.filename === filename);
+  try {
+    await invoke(COMMANDS.IMPORT_MODEL, { path, model: known ? null : el("model").value, link: false });
+    showStatus(t("model.imported"));
+  } catch (e) {
+    showStatus(String(e), true);
+  }
+  await loadModels();
+}
+
+let historyDir = "";
+
+function renderHistoryDir() {
+  el("history-dir").textContent = historyDir || t("history.defaultDir");
+  el("history-dir").title = historyDir;
+}
+
+function updateHistoryVisibility() {
+  el("history-section").classList.toggle("hidden", !el("history-enabled").checked);
+}
+
+async function onHistoryDirChoose() {
+  const dir = await window.__TAURI__.dialog.open({ directory: true, multiple: false });
+  if (!dir) return;
+  historyDir = dir;
+  renderHistoryDir();
+}
+
+function updatePreRollVisibility() {
+  el("pre-roll-length-row").classList.toggle("hidden", !el("pre-roll-enabled").checked);
+}
+
 // --- Init ---
 
 window.addEventListener("DOMContentLoaded", async () => {
@@ -418,19 +729,41 @@ window.addEventListener("DOMContentLoaded", async () => {
     const s = await invoke(COMMANDS.GET_SETTINGS);
     setPttKey(s.hotkey_dictation || s.ptt_key);
     el("language").value = s.language;
+    buildAutoLanguages();
+    loadAutoLanguages(s.auto_languages || []);
+    updateLanguageVisibility();
     el("engine").value = s.engine;
-    el("model").value = s.model;
+    modelCatalog.active = s.model;
     el("groq-api-key").value = s.groq_api_key;
+    el("custom-engine-url").value = s.custom_engine_url || "";
+    el("custom-engine-key").value = s.custom_engine_key || "";
+    el("custom-engine-model").value = s.custom_engine_model || "whisper-1";
+    el("model-mirror-url").value = s.model_mirror_url || "";
+    el("accuracy-profile").value = s.accuracy_profile || "fast";
+    loadDecoding(s.decoding || {});
+    updateAccuracyVisibility();
     el("opacity").value = s.window_opacity;
     el("opacity-value").textContent = Math.round(s.window_opacity * 100) + "%";
     el("auto-start").checked = s.auto_start;
     setRecordingMode(s.recording_mode || "hold");
+    el("long-form-max-minutes").value = String(s.long_form_max_minutes || 0);
+    el("long-form-keep-audio").checked = s.long_form_keep_audio || false;
+    el("history-enabled").checked = s.history_enabled || false;
+    el("history-retention-days").value = String(s.history_retention_days ?? 90);
+    historyDir = s.history_dir || "";
+    updateHistoryVisibility();
+    selectedInputDevice = s.input_device || "";
+    el("pre-roll-enabled").checked = s.pre_roll_enabled || false;
+    el("pre-roll-ms").value = String(s.pre_roll_ms || 500);
+    updatePreRollVisibility();
     loadDictFromString(s.dictionary || "");
     loadDictPacks(s.dictionary_packs || []);
+    await loadHallucinationFilter(s.hallucination_filter || {});
     dictTagsSnapshot = [...dictTags];
     el("llm-enabled").checked = s.llm_enabled || false;
     el("llm-model").value = s.llm_model || "llama-3.3-70b-versatile";
     el("app-aware-style").checked = s.app_aware_style !== false;
+    el("vad-enabled").checked = s.vad_enabled !== false;
     el("llm-provider").value = s.llm_provider || "groq";
     el("ollama-url").value = s.ollama_url || "http://localhost:11434";
     el("ollama-model").value = s.ollama_model || "llama3.2";
@@ -450,10 +783,15 @@ window.addEventListener("DOMContentLoaded", async () => {
     currentClipboardRewriteKey = s.hotkey_clipboard_rewrite || "";
     el("voice-command-record").textContent = currentVoiceCommandKey ? displayNameFor(currentVoiceCommandKey) : t("hotkey.notSet");
     el("clipboard-rewrite-record").textContent = currentClipboardRewriteKey ? displayNameFor(currentClipboardRewriteKey) : t("hotkey.notSet");
+    currentSpeechToEnglishKey = s.hotkey_speech_to_english || "";
+    el("speech-to-english-record").textContent = currentSpeechToEnglishKey ? displayNameFor(currentSpeechToEnglishKey) : t("hotkey.notSet");
     el("translate-language").value = s.translate_language || "en";
     // Apply locale
     el("ui-locale").value = s.ui_locale || "en";
     applyLocale(s.ui_locale || "en");
+    renderHistoryDir();
+    await loadInputDevices();
+    await loadModels();
   } catch (e) {
     showStatus(t("status.loadFailed"), true);
   }
@@ -489,12 +827,33 @@ window.addEventListener("DOMContentLoaded", async () => {
     });
   });
 
+  el("speech-to-english-record").addEventListener("click", () => {
+    startComboRecording("speech-to-english-record", (v) => {
+      if (v !== undefined) currentSpeechToEnglishKey = v;
+      return currentSpeechToEnglishKey;
+    });
+  });
+
   // Global keydown/keyup for recording
   document.addEventListener("keydown", handleKeyDown);
   document.addEventListener("keyup", handleKeyUp);
 
   // Engine toggle
   el("engine").addEventListener("change", updateEngineVisibility);
+  el("language").addEventListener("change", updateLanguageVisibility);
+  el("accuracy-profile").addEventListener("change", updateAccuracyVisibility);
+  el("model").addEventListener("change", updateModelAction);
+  el("btn-model-action").addEventListener("click", onModelAction);
+  el("btn-model-import").addEventListener("click", onModelImport);
+  el("history-enabled").addEventListener("change", updateHistoryVisibility);
+  el("btn-history-dir").addEventListener("click", onHistoryDirChoose);
+  el("btn-open-history").addEventListener("click", () => invoke(COMMANDS.OPEN_HISTORY));
+  await listen(EVENTS.MODEL_DOWNLOAD_PROGRESS, (event) => {
+    const { model, stage, downloaded, total } = event.payload;
+    if (model !== downloadingModel || stage !== "downloading" || !total) return;
+    const percent = Math.floor((downloaded / total) * 100);
+    el("btn-model-action").textContent = t("model.cancelDownload").replace("{percent}", percent);
+  });
 
   // LLM toggle
   el("llm-enabled").addEventListener("change", updateLlmVisibility);
@@ -505,6 +864,7 @@ window.addEventListener("DOMContentLoaded", async () => {
   // UI locale
   el("ui-locale").addEventListener("change", () => {
     applyLocale(el("ui-locale").value);
+    renderHistoryDir();
   });
 
   // Recording mode segmented control
@@ -538,6 +898,14 @@ window.addEventListener("DOMContentLoaded", async () => {
   // Undo button
   el("dict-undo-btn").addEventListener("click", doDictUndo);
 
+  el("filter-input").addEventListener("keydown", (e) => {
+    if (e.code === "Enter") {
+      e.preventDefault();
+      addFilterRule(el("filter-input").value);
+      el("filter-input").value = "";
+    }
+  });
+
   // Text replacement — add rule on Enter in replace field
   el("replace-value-new").addEventListener("keydown", (e) => {
     if (e.code === "Enter") {
@@ -572,6 +940,11 @@ window.addEventListener("DOMContentLoaded", async () => {
     }
   });
 
+  el("pre-roll-enabled").addEventListener("change", updatePreRollVisibility);
+
+  // Devices may have been plugged in or removed while the window was hidden
+  window.addEventListener("focus", loadInputDevices);
+
   // Opacity slider
   el("opacity").addEventListener("input", () => {
     el("opacity-value").textContent = Math.round(el("opacity").value * 100) + "%";
@@ -583,16 +956,32 @@ window.addEventListener("DOMContentLoaded", async () => {
       ptt_key: currentPttKey,
       hotkey_dictation: currentPttKey,
       language: el("language").value,
+      auto_languages: getAutoLanguages(),
       engine: el("engine").value,
       model: el("model").value,
       groq_api_key: el("groq-api-key").value,
+      custom_engine_url: el("custom-engine-url").value.trim(),
+      custom_engine_key: el("custom-engine-key").value,
+      custom_engine_model: el("custom-engine-model").value.trim() || "whisper-1",
+      model_mirror_url: el("model-mirror-url").value.trim(),
+      accuracy_profile: el("accuracy-profile").value,
+      decoding: getDecoding(),
       window_opacity: parseFloat(el("opacity").value),
       auto_start: el("auto-start").checked,
       recording_mode: recordingMode,
+      long_form_max_minutes: Math.max(0, parseInt(el("long-form-max-minutes").value, 10) || 0),
+      long_form_keep_audio: el("long-form-keep-audio").checked,
+      history_enabled: el("history-enabled").checked,
+      history_dir: historyDir,
+      history_retention_days: Math.max(0, parseInt(el("history-retention-days").value, 10) || 0),
+      input_device: el("input-device").value,
+      pre_roll_enabled: el("pre-roll-enabled").checked,
+      pre_roll_ms: parseInt(el("pre-roll-ms").value, 10),
       dictionary: getDictString(),
       llm_enabled: el("llm-enabled").checked,
       llm_model: el("llm-model").value,
       app_aware_style: el("app-aware-style").checked,
+      vad_enabled: el("vad-enabled").checked,
       llm_provider: el("llm-provider").value,
       ollama_url: el("ollama-url").value,
       ollama_model: el("ollama-model").value,
@@ -604,8 +993,10 @@ window.addEventListener("DOMContentLoaded", async () => {
       hotkey_translate: currentTranslateKey,
       hotkey_voice_command: currentVoiceCommandKey,
       hotkey_clipboard_rewrite: currentClipboardRewriteKey,
+      hotkey_speech_to_english: currentSpeechToEnglishKey,
       translate_language: el("translate-language").value,
       dictionary_packs: getEnabledPacks(),
+      hallucination_filter: getHallucinationFilter(),
       text_replacements: collectReplacements(),
     };
 